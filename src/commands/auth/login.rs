@@ -2,8 +2,11 @@ use std::process::ExitCode;
 use std::sync::Arc;
 use std::time::Duration;
 
+use tokio::io::AsyncReadExt;
+
+use chrono::Utc;
 use clap::Parser;
-use color_eyre::eyre::{Context, ContextCompat};
+use color_eyre::eyre::{bail, Context, ContextCompat};
 use dialoguer::theme::ColorfulTheme;
 use dialoguer::{Input, Password};
 use indicatif::ProgressBar;
@@ -15,7 +18,22 @@ use crate::Result;
 
 #[derive(Debug, Clone, PartialEq, Parser)]
 #[command(author, rename_all = "kebab-case")]
-pub struct Opts {}
+pub struct Opts {
+    /// The email address to log in with (prompted for, if omitted)
+    #[arg(long, env = "MEGA_EMAIL")]
+    email: Option<String>,
+    /// Read the password from the standard input
+    /// (otherwise, it is read from the `MEGA_PASSWORD` environment variable, or prompted for)
+    #[arg(long)]
+    password_stdin: bool,
+    /// The MFA code to use, if the account requires one
+    /// (generated from the configured MFA secret, or prompted for, if omitted)
+    #[arg(long, conflicts_with = "mfa_stdin")]
+    mfa_code: Option<String>,
+    /// Read the MFA code from the standard input, if the account requires one
+    #[arg(long, conflicts_with = "password_stdin")]
+    mfa_stdin: bool,
+}
 
 impl Opts {
     pub fn may_need_user_session(&self) -> bool {
@@ -23,16 +41,36 @@ impl Opts {
     }
 }
 
-pub async fn handle(mut config: Config, mega: &mut Arc<mega::Client>, _: Opts) -> Result<ExitCode> {
+pub async fn handle(
+    mut config: Config,
+    mega: &mut Arc<mega::Client>,
+    opts: Opts,
+) -> Result<ExitCode> {
     let theme = ColorfulTheme::default();
 
-    let email: String = Input::with_theme(&theme)
-        .with_prompt("Enter email address")
-        .interact_text()?;
-
-    let password: String = Password::with_theme(&theme)
-        .with_prompt("Enter password")
-        .interact()?;
+    let email = match opts.email {
+        Some(email) => email,
+        None if *USER_ATTENDED => Input::with_theme(&theme)
+            .with_prompt("Enter email address")
+            .interact_text()?,
+        None => bail!("missing email address (use `--email` or `MEGA_EMAIL`)"),
+    };
+
+    let mfa_secret = match config {
+        Config::V1(ref config) => config.auth.mfa_secret.clone(),
+    };
+
+    let password = if opts.password_stdin {
+        read_stdin_secret().await?
+    } else if let Ok(password) = std::env::var("MEGA_PASSWORD") {
+        password
+    } else if *USER_ATTENDED {
+        Password::with_theme(&theme)
+            .with_prompt("Enter password")
+            .interact()?
+    } else {
+        bail!("missing password (use `--password-stdin` or `MEGA_PASSWORD`)");
+    };
 
     let maybe_bar = USER_ATTENDED.then(|| {
         let bar = ProgressBar::new_spinner();
@@ -54,9 +92,20 @@ pub async fn handle(mut config: Config, mega: &mut Arc<mega::Client>, _: Opts) -
         code: mega::ErrorCode::EMFAREQUIRED,
     }) = result
     {
-        let mfa: String = Input::with_theme(&theme)
-            .with_prompt("Enter MFA code")
-            .interact_text()?;
+        let mfa = if let Some(mfa) = opts.mfa_code {
+            mfa
+        } else if opts.mfa_stdin {
+            read_stdin_secret().await?
+        } else if let Some(secret) = mfa_secret.as_deref() {
+            utils::totp::generate_code(secret, Utc::now().timestamp())
+                .context("could not generate MFA code")?
+        } else if *USER_ATTENDED {
+            Input::with_theme(&theme)
+                .with_prompt("Enter MFA code")
+                .interact_text()?
+        } else {
+            bail!("missing MFA code (use `--mfa-code`, `--mfa-stdin` or the `auth.mfa_secret` configuration)");
+        };
 
         let maybe_bar = USER_ATTENDED.then(|| {
             let bar = ProgressBar::new_spinner();
@@ -94,3 +143,14 @@ pub async fn handle(mut config: Config, mega: &mut Arc<mega::Client>, _: Opts) -
 
     Ok(ExitCode::SUCCESS)
 }
+
+/// Reads a secret from the standard input, without its trailing newline.
+async fn read_stdin_secret() -> Result<String> {
+    let mut secret = String::default();
+    tokio::io::stdin()
+        .read_to_string(&mut secret)
+        .await
+        .context("could not read from the standard input")?;
+
+    Ok(secret.trim_end_matches(['\r', '\n']).to_string())
+}
@@ -71,9 +71,9 @@ pub async fn handle(_: Config, mega: &mega::Client, opts: Opts) -> Result<ExitCo
         nodes
     };
 
-    let node = if opts.remote.starts_with("H:") {
+    let node = if let Some(handle) = opts.remote.strip_prefix("H:") {
         nodes
-            .get_node_by_handle(&opts.remote[2..])
+            .get_node_by_handle(handle)
             .context("could not find node (by handle)")?
     } else {
         nodes
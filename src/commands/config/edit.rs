@@ -60,10 +60,11 @@ pub async fn handle(_: Config, _: &mega::Client, opts: Opts) -> Result<ExitCode>
         .await
         .context("could not spawn EDITOR process and wait for it to complete")?;
 
-    let code = status
-        .success()
-        .then_some(ExitCode::SUCCESS)
-        .unwrap_or(ExitCode::FAILURE);
+    let code = if status.success() {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    };
 
     if status.success() {
         crate::success!(
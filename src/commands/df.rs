@@ -0,0 +1,116 @@
+use std::process::ExitCode;
+use std::time::Duration;
+
+use clap::Parser;
+use color_eyre::eyre::Context;
+use indicatif::{DecimalBytes, ProgressBar};
+use serde::Serialize;
+
+use crate::config::Config;
+use crate::utils;
+use crate::utils::output::OutputFormat;
+use crate::utils::terminal::USER_ATTENDED;
+use crate::Result;
+
+#[derive(Debug, Clone, PartialEq, Parser)]
+#[command(author, rename_all = "kebab-case")]
+pub struct Opts {
+    /// Display exact sizes in bytes, instead of human-readable ones
+    #[arg(long, short)]
+    bytes: bool,
+    /// The format in which to display the storage usage
+    #[arg(long, short, value_enum, default_value_t = OutputFormat::Text)]
+    output: OutputFormat,
+}
+
+impl Opts {
+    pub fn may_need_user_session(&self) -> bool {
+        true
+    }
+}
+
+/// The storage usage of the account (all sizes are in bytes).
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct StorageUsage {
+    /// The total storage space of the account.
+    total: u64,
+    /// The storage space currently in use.
+    used: u64,
+    /// The storage space still available.
+    free: u64,
+    /// The storage space used by the Cloud Drive.
+    cloud_drive: u64,
+    /// The storage space used by the Inbox.
+    inbox: u64,
+    /// The storage space used by the Rubbish Bin.
+    rubbish_bin: u64,
+}
+
+pub async fn handle(_: Config, mega: &mega::Client, opts: Opts) -> Result<ExitCode> {
+    let maybe_bar = USER_ATTENDED.then(|| {
+        let bar = ProgressBar::new_spinner();
+        bar.set_style(utils::terminal::spinner_style());
+        bar.set_message("fetching storage quotas and MEGA nodes...");
+        bar.enable_steady_tick(Duration::from_millis(75));
+        bar
+    });
+
+    // TODO: report the transfer quota as well, once it is exposed by the `mega` crate.
+    let (quotas, nodes) = futures::try_join!(
+        async {
+            mega.get_storage_quotas()
+                .await
+                .context("could not fetch storage quotas")
+        },
+        async {
+            mega.fetch_own_nodes()
+                .await
+                .context("could net fetch own MEGA nodes")
+        },
+    )?;
+
+    if let Some(bar) = maybe_bar {
+        bar.finish_and_clear();
+    }
+
+    let root_size = |maybe_root: Option<&mega::Node>| {
+        maybe_root.map_or(0, |root| utils::nodes::total_size(&nodes, root))
+    };
+
+    let usage = StorageUsage {
+        total: quotas.memory_total,
+        used: quotas.memory_used,
+        free: quotas.memory_total.saturating_sub(quotas.memory_used),
+        cloud_drive: root_size(nodes.cloud_drive()),
+        inbox: root_size(nodes.inbox()),
+        rubbish_bin: root_size(nodes.rubbish_bin()),
+    };
+
+    if opts.output.is_json() {
+        utils::output::print_json_item(opts.output, &usage)?;
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    let format_size = |size: u64| {
+        if opts.bytes {
+            size.to_string()
+        } else {
+            DecimalBytes(size).to_string()
+        }
+    };
+
+    let used_percentage = if usage.total > 0 {
+        (usage.used as f64) * 100.0 / (usage.total as f64)
+    } else {
+        0.0
+    };
+
+    crate::info!(to: std::io::stdout(), "total = `{0}`", format_size(usage.total))?;
+    crate::info!(to: std::io::stdout(), "used = `{0}` ({used_percentage:.1}%)", format_size(usage.used))?;
+    crate::info!(to: std::io::stdout(), "free = `{0}`", format_size(usage.free))?;
+    crate::info!(to: std::io::stdout(), "cloud_drive = `{0}`", format_size(usage.cloud_drive))?;
+    crate::info!(to: std::io::stdout(), "inbox = `{0}`", format_size(usage.inbox))?;
+    crate::info!(to: std::io::stdout(), "rubbish_bin = `{0}`", format_size(usage.rubbish_bin))?;
+
+    Ok(ExitCode::SUCCESS)
+}
@@ -0,0 +1,171 @@
+use std::process::ExitCode;
+use std::time::Duration;
+
+use clap::Parser;
+use color_eyre::eyre::{Context, ContextCompat};
+use indicatif::{DecimalBytes, ProgressBar};
+use serde::Serialize;
+
+use crate::config::Config;
+use crate::utils;
+use crate::utils::output::OutputFormat;
+use crate::utils::terminal::USER_ATTENDED;
+use crate::Result;
+
+#[derive(Debug, Clone, PartialEq, Parser)]
+#[command(author, rename_all = "kebab-case")]
+pub struct Opts {
+    /// Only display totals for folders at most this many levels below the target
+    #[arg(long, short = 'd')]
+    max_depth: Option<usize>,
+    /// Only display the total for the target itself (same as `--max-depth 0`)
+    #[arg(long, short, conflicts_with = "max_depth")]
+    summarize: bool,
+    /// Display exact sizes in bytes, instead of human-readable ones
+    #[arg(long, short)]
+    bytes: bool,
+    /// The format in which to display the sizes
+    #[arg(long, short, value_enum, default_value_t = OutputFormat::Text)]
+    output: OutputFormat,
+    /// Path (eg. `/Root/folder`) or handle (eg. `H:gZlB3JxS`) to the MEGA node to measure (all roots, if omitted)
+    path: Option<String>,
+}
+
+impl Opts {
+    pub fn may_need_user_session(&self) -> bool {
+        true
+    }
+}
+
+/// The disk usage of a node.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct DiskUsage {
+    /// The handle of the node.
+    handle: String,
+    /// The full path to the node.
+    path: String,
+    /// The total size (in bytes) of the files below the node.
+    size: u64,
+    /// The number of files below the node.
+    files: u64,
+}
+
+pub async fn handle(_: Config, mega: &mega::Client, opts: Opts) -> Result<ExitCode> {
+    let nodes = {
+        let maybe_bar = USER_ATTENDED.then(|| {
+            let bar = ProgressBar::new_spinner();
+            bar.set_style(utils::terminal::spinner_style());
+            bar.set_message("fetching MEGA nodes...");
+            bar.enable_steady_tick(Duration::from_millis(75));
+            bar
+        });
+
+        let nodes = mega
+            .fetch_own_nodes()
+            .await
+            .context("could net fetch own MEGA nodes")?;
+
+        if let Some(bar) = maybe_bar {
+            bar.finish_and_clear();
+        }
+
+        nodes
+    };
+
+    let targets: Vec<_> = if let Some(path) = opts.path.as_deref() {
+        let node = if let Some(handle) = path.strip_prefix("H:") {
+            nodes
+                .get_node_by_handle(handle)
+                .context("could not find node (by handle)")?
+        } else {
+            nodes
+                .get_node_by_path(path)
+                .context("could not find node (by path)")?
+        };
+
+        vec![node]
+    } else {
+        let mut roots: Vec<_> = nodes.roots().collect();
+        roots.sort_unstable_by_key(|node| node.name());
+        roots
+    };
+
+    let max_depth = if opts.summarize {
+        Some(0)
+    } else {
+        opts.max_depth
+    };
+
+    let mut usages = Vec::default();
+    for target in targets {
+        collect_disk_usage(&nodes, target, 0, max_depth, &mut usages);
+    }
+
+    if opts.output.is_json() {
+        utils::output::print_json_items(opts.output, &usages)?;
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    let sizes: Vec<_> = usages
+        .iter()
+        .map(|usage| {
+            if opts.bytes {
+                usage.size.to_string()
+            } else {
+                DecimalBytes(usage.size).to_string()
+            }
+        })
+        .collect();
+
+    let width = sizes.iter().map(String::len).max().unwrap_or_default();
+
+    for (size, usage) in sizes.iter().zip(usages.iter()) {
+        println!("{size:>width$}  {0}", usage.path);
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Recursively computes the disk usage of a node, recording the usage of folders up to the given depth
+/// (children are recorded before their parents).
+fn collect_disk_usage(
+    nodes: &mega::Nodes,
+    node: &mega::Node,
+    depth: usize,
+    max_depth: Option<usize>,
+    usages: &mut Vec<DiskUsage>,
+) -> (u64, u64) {
+    if node.kind().is_file() {
+        // files are only recorded when targeted directly.
+        if depth == 0 {
+            usages.push(DiskUsage {
+                handle: node.handle().to_string(),
+                path: utils::nodes::construct_full_path(nodes, node),
+                size: node.size(),
+                files: 1,
+            });
+        }
+        return (node.size(), 1);
+    }
+
+    let (size, files) = node
+        .children()
+        .iter()
+        .filter_map(|handle| nodes.get_node_by_handle(handle))
+        .fold((0, 0), |(size, files), child| {
+            let (child_size, child_files) =
+                collect_disk_usage(nodes, child, depth + 1, max_depth, usages);
+            (size + child_size, files + child_files)
+        });
+
+    if max_depth.is_none_or(|max_depth| depth <= max_depth) {
+        usages.push(DiskUsage {
+            handle: node.handle().to_string(),
+            path: utils::nodes::construct_full_path(nodes, node),
+            size,
+            files,
+        });
+    }
+
+    (size, files)
+}
@@ -0,0 +1,383 @@
+use std::io::Write;
+use std::process::ExitCode;
+use std::time::Duration;
+
+use chrono::Utc;
+use clap::{Parser, ValueEnum};
+use color_eyre::eyre::{bail, Context, ContextCompat};
+use indicatif::ProgressBar;
+use regex::Regex;
+
+use crate::config::Config;
+use crate::utils;
+use crate::utils::terminal::USER_ATTENDED;
+use crate::Result;
+
+#[derive(Debug, Clone, PartialEq, Parser)]
+#[command(author, rename_all = "kebab-case")]
+pub struct Opts {
+    /// Only match nodes whose name matches a glob pattern (eg. `*.mkv`)
+    #[arg(long)]
+    name: Option<String>,
+    /// Only match nodes whose name matches a glob pattern, case-insensitively
+    #[arg(long)]
+    iname: Option<String>,
+    /// Only match nodes whose full path matches a regular expression
+    #[arg(long)]
+    regex: Option<String>,
+    /// Only match nodes of the given type
+    #[arg(long = "type", short = 't', value_enum)]
+    node_type: Option<NodeType>,
+    /// Only match files of the given size (eg. `+100M` for more than 100 MiB, `-1k` for less than 1 KiB)
+    #[arg(long, value_parser(parse_size_filter), allow_hyphen_values = true)]
+    size: Option<SizeFilter>,
+    /// Only match nodes modified less than the given duration ago (eg. `7d`)
+    #[arg(long, value_parser(crate::serde_utils::duration::parse_duration))]
+    newer: Option<Duration>,
+    /// Only match nodes modified more than the given duration ago (eg. `30d`)
+    #[arg(long, value_parser(crate::serde_utils::duration::parse_duration))]
+    older: Option<Duration>,
+    /// Display node handles (eg. `H:gZlB3JxS`) instead of full paths
+    #[arg(long, short = 'H')]
+    show_handles: bool,
+    /// Separate results with NUL characters instead of newlines (for `xargs -0`)
+    #[arg(long, short = '0')]
+    print0: bool,
+    /// Path (eg. `/Root/folder`) or handle (eg. `H:gZlB3JxS`) to the MEGA node to search from (all roots, if omitted)
+    path: Option<String>,
+}
+
+/// The type of nodes to match.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+#[value(rename_all = "kebab-case")]
+pub enum NodeType {
+    /// Files
+    #[value(name = "f")]
+    File,
+    /// Folders
+    #[value(name = "d")]
+    Folder,
+}
+
+/// A predicate on the size of files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SizeFilter {
+    /// Strictly larger than the given size (in bytes).
+    Greater(u64),
+    /// Strictly smaller than the given size (in bytes).
+    Less(u64),
+    /// Exactly the given size (in bytes).
+    Exact(u64),
+}
+
+impl SizeFilter {
+    fn matches(self, size: u64) -> bool {
+        match self {
+            SizeFilter::Greater(threshold) => size > threshold,
+            SizeFilter::Less(threshold) => size < threshold,
+            SizeFilter::Exact(threshold) => size == threshold,
+        }
+    }
+}
+
+/// Parses a size predicate, like `find -size` does (eg. `+100M`, `-1k` or `4096`).
+///
+/// The supported units are `c` (bytes, the default), `k` (KiB), `M` (MiB), `G` (GiB) and `T` (TiB).
+fn parse_size_filter(value: &str) -> Result<SizeFilter, String> {
+    let value = value.trim();
+    let (constructor, value): (fn(u64) -> SizeFilter, &str) =
+        if let Some(value) = value.strip_prefix('+') {
+            (SizeFilter::Greater, value)
+        } else if let Some(value) = value.strip_prefix('-') {
+            (SizeFilter::Less, value)
+        } else {
+            (SizeFilter::Exact, value)
+        };
+
+    let position = value.chars().take_while(|it| it.is_ascii_digit()).count();
+    let (number_str, unit_str) = value.split_at(position);
+    let number: u64 = number_str
+        .parse()
+        .map_err(|_| format!("invalid size: `{value}`"))?;
+
+    let multiplier: u64 = match unit_str {
+        "" | "c" => 1,
+        "k" | "K" => 1 << 10,
+        "M" => 1 << 20,
+        "G" => 1 << 30,
+        "T" => 1 << 40,
+        _ => return Err(format!("invalid size unit: `{unit_str}`")),
+    };
+
+    let size = number
+        .checked_mul(multiplier)
+        .ok_or_else(|| format!("size is too large: `{value}`"))?;
+
+    Ok(constructor(size))
+}
+
+/// Converts a glob pattern (supporting `*`, `?` and `[...]`) into an equivalent regular expression.
+fn glob_to_regex(pattern: &str, case_insensitive: bool) -> Result<Regex> {
+    let mut expression = String::from(if case_insensitive { "(?i)^" } else { "^" });
+
+    let mut chars = pattern.chars();
+    while let Some(ch) = chars.next() {
+        match ch {
+            '*' => expression.push_str(".*"),
+            '?' => expression.push('.'),
+            '[' => {
+                let mut class_chars = chars.clone();
+                let is_negated = class_chars.clone().next() == Some('!');
+                if is_negated {
+                    class_chars.next();
+                }
+
+                // a leading `]` is part of the class, as per POSIX.
+                let mut members = Vec::default();
+                if class_chars.clone().next() == Some(']') {
+                    members.push(']');
+                    class_chars.next();
+                }
+
+                let mut is_closed = false;
+                for it in class_chars.by_ref() {
+                    if it == ']' {
+                        is_closed = true;
+                        break;
+                    }
+                    members.push(it);
+                }
+
+                if !is_closed {
+                    // an unclosed class is matched literally, like shells do.
+                    expression.push_str(r"\[");
+                    continue;
+                }
+
+                chars = class_chars;
+                expression.push('[');
+                if is_negated {
+                    expression.push('^');
+                }
+                for member in members {
+                    // escape the characters that are special within regex classes (but not ranges).
+                    if matches!(member, '\\' | '[' | ']' | '^' | '&' | '~') {
+                        expression.push('\\');
+                    }
+                    expression.push(member);
+                }
+                expression.push(']');
+            }
+            _ => expression.push_str(&regex::escape(ch.encode_utf8(&mut [0u8; 4]))),
+        }
+    }
+
+    expression.push('$');
+
+    Regex::new(&expression).context("invalid glob pattern")
+}
+
+impl Opts {
+    pub fn may_need_user_session(&self) -> bool {
+        true
+    }
+}
+
+pub async fn handle(_: Config, mega: &mega::Client, opts: Opts) -> Result<ExitCode> {
+    if opts.name.is_some() && opts.iname.is_some() {
+        bail!("`--name` and `--iname` cannot be used together");
+    }
+
+    let name_regex = match (opts.name.as_deref(), opts.iname.as_deref()) {
+        (Some(pattern), _) => Some(glob_to_regex(pattern, false)?),
+        (_, Some(pattern)) => Some(glob_to_regex(pattern, true)?),
+        (None, None) => None,
+    };
+
+    let path_regex = opts
+        .regex
+        .as_deref()
+        .map(Regex::new)
+        .transpose()
+        .context("invalid regular expression")?;
+
+    let nodes = {
+        let maybe_bar = USER_ATTENDED.then(|| {
+            let bar = ProgressBar::new_spinner();
+            bar.set_style(utils::terminal::spinner_style());
+            bar.set_message("fetching MEGA nodes...");
+            bar.enable_steady_tick(Duration::from_millis(75));
+            bar
+        });
+
+        let nodes = mega
+            .fetch_own_nodes()
+            .await
+            .context("could net fetch own MEGA nodes")?;
+
+        if let Some(bar) = maybe_bar {
+            bar.finish_and_clear();
+        }
+
+        nodes
+    };
+
+    let mut stack: Vec<_> = if let Some(path) = opts.path.as_deref() {
+        let node = if let Some(handle) = path.strip_prefix("H:") {
+            nodes
+                .get_node_by_handle(handle)
+                .context("could not find node (by handle)")?
+        } else {
+            nodes
+                .get_node_by_path(path)
+                .context("could not find node (by path)")?
+        };
+
+        vec![node]
+    } else {
+        let mut roots: Vec<_> = nodes.roots().collect();
+        roots.sort_unstable_by_key(|node| std::cmp::Reverse(node.name()));
+        roots
+    };
+
+    let now = Utc::now();
+    let newer_than = opts
+        .newer
+        .map(|duration| chrono::Duration::from_std(duration).map(|duration| now - duration))
+        .transpose()
+        .context("duration is out of range")?;
+    let older_than = opts
+        .older
+        .map(|duration| chrono::Duration::from_std(duration).map(|duration| now - duration))
+        .transpose()
+        .context("duration is out of range")?;
+
+    let separator = if opts.print0 { '\0' } else { '\n' };
+    let mut stdout = std::io::stdout().lock();
+
+    // depth-first traversal, visiting children in name order.
+    while let Some(node) = stack.pop() {
+        let mut children: Vec<_> = node
+            .children()
+            .iter()
+            .filter_map(|handle| nodes.get_node_by_handle(handle))
+            .collect();
+        children.sort_unstable_by_key(|node| std::cmp::Reverse(node.name()));
+        stack.extend(children);
+
+        let matches_type = match opts.node_type {
+            Some(NodeType::File) => node.kind().is_file(),
+            Some(NodeType::Folder) => !node.kind().is_file(),
+            None => true,
+        };
+        if !matches_type {
+            continue;
+        }
+
+        if let Some(regex) = name_regex.as_ref() {
+            if !regex.is_match(node.name()) {
+                continue;
+            }
+        }
+
+        if let Some(filter) = opts.size {
+            if !node.kind().is_file() || !filter.matches(node.size()) {
+                continue;
+            }
+        }
+
+        if newer_than.is_some() || older_than.is_some() {
+            let Some(modified_at) = node.modified_at().or(node.created_at()) else {
+                continue;
+            };
+            if newer_than.is_some_and(|date| modified_at < date)
+                || older_than.is_some_and(|date| modified_at > date)
+            {
+                continue;
+            }
+        }
+
+        let full_path = utils::nodes::construct_full_path(&nodes, node);
+
+        if let Some(regex) = path_regex.as_ref() {
+            if !regex.is_match(&full_path) {
+                continue;
+            }
+        }
+
+        if opts.show_handles {
+            write!(stdout, "H:{0}{separator}", node.handle())?;
+        } else {
+            write!(stdout, "{full_path}{separator}")?;
+        }
+    }
+
+    stdout.flush()?;
+
+    Ok(ExitCode::SUCCESS)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_size_filters() {
+        assert_eq!(
+            parse_size_filter("+100M"),
+            Ok(SizeFilter::Greater(100 << 20))
+        );
+        assert_eq!(parse_size_filter("-1k"), Ok(SizeFilter::Less(1 << 10)));
+        assert_eq!(parse_size_filter("4096"), Ok(SizeFilter::Exact(4096)));
+        assert!(parse_size_filter("20000000T").is_err());
+        assert!(parse_size_filter("1Z").is_err());
+        assert!(parse_size_filter("+").is_err());
+    }
+
+    #[test]
+    fn matches_wildcards() {
+        let regex = glob_to_regex("*.mkv", false).unwrap();
+        assert!(regex.is_match("movie.mkv"));
+        assert!(regex.is_match(".mkv"));
+        assert!(!regex.is_match("movie.mkv.part"));
+        assert!(!regex.is_match("movie.MKV"));
+
+        let regex = glob_to_regex("*.mkv", true).unwrap();
+        assert!(regex.is_match("movie.MKV"));
+
+        let regex = glob_to_regex("file?.txt", false).unwrap();
+        assert!(regex.is_match("file1.txt"));
+        assert!(!regex.is_match("file10.txt"));
+    }
+
+    #[test]
+    fn matches_classes() {
+        let regex = glob_to_regex("[!a]*", false).unwrap();
+        assert!(regex.is_match("beta"));
+        assert!(!regex.is_match("alpha"));
+
+        let regex = glob_to_regex("[a-c]", false).unwrap();
+        assert!(regex.is_match("b"));
+        assert!(!regex.is_match("d"));
+
+        let regex = glob_to_regex("[]a]", false).unwrap();
+        assert!(regex.is_match("]"));
+        assert!(regex.is_match("a"));
+
+        let regex = glob_to_regex("[[]", false).unwrap();
+        assert!(regex.is_match("["));
+
+        let regex = glob_to_regex("[]", false).unwrap();
+        assert!(regex.is_match("[]"));
+    }
+
+    #[test]
+    fn matches_unclosed_classes_literally() {
+        let regex = glob_to_regex("file[1", false).unwrap();
+        assert!(regex.is_match("file[1"));
+        assert!(!regex.is_match("file1"));
+
+        let regex = glob_to_regex("[!", false).unwrap();
+        assert!(regex.is_match("[!"));
+    }
+}
@@ -50,7 +50,7 @@ pub async fn handle(_: Config, mega: &mega::Client, _: Opts) -> Result<ExitCode>
         let maybe_bar = USER_ATTENDED.then(|| {
             let bar = ProgressBar::new_spinner();
             bar.set_style(utils::terminal::spinner_style());
-            bar.set_message(format!("waiting for new events (CTRL-C to terminate)..."));
+            bar.set_message("waiting for new events (CTRL-C to terminate)...");
             bar.enable_steady_tick(Duration::from_millis(75));
             bar
         });
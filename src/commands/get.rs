@@ -1,4 +1,5 @@
 use std::collections::VecDeque;
+use std::ffi::OsString;
 use std::path::{Path, PathBuf};
 use std::process::ExitCode;
 use std::sync::Arc;
@@ -10,9 +11,9 @@ use async_read_progress::AsyncReadProgressExt;
 use clap::Parser;
 use color_eyre::eyre::{bail, Context, ContextCompat};
 use filetime::FileTime;
-use futures::future::Either;
+use futures::AsyncWriteExt;
 use indicatif::{MultiProgress, ProgressBar};
-use tokio_util::compat::{TokioAsyncReadCompatExt, TokioAsyncWriteCompatExt};
+use tokio_util::compat::TokioAsyncWriteCompatExt;
 
 use crate::config::Config;
 use crate::utils::terminal::USER_ATTENDED;
@@ -22,7 +23,7 @@ use crate::{utils, Error};
 #[derive(Debug, Clone, PartialEq, Parser)]
 #[command(author, rename_all = "kebab-case")]
 pub struct Opts {
-    /// Path to output file
+    /// Path to output file (`-` to write the file's contents to the standard output)
     #[arg(long, short)]
     output: Option<PathBuf>,
     /// Whether to download all nodes
@@ -82,7 +83,12 @@ pub async fn handle(_: Config, mega: &Arc<mega::Client>, opts: Opts) -> Result<E
         Arc::new(nodes)
     };
 
+    let to_stdout = opts.output.as_deref() == Some(Path::new("-"));
+
     if opts.all {
+        if to_stdout {
+            bail!("cannot write multiple nodes to the standard output");
+        }
         return download_all_nodes(mega, &nodes, opts).await;
     }
 
@@ -90,17 +96,22 @@ pub async fn handle(_: Config, mega: &Arc<mega::Client>, opts: Opts) -> Result<E
         bail!("missing target path");
     };
 
-    let root = if path.starts_with("H:") {
+    let root = if let Some(handle) = path.strip_prefix("H:") {
         nodes
-            .get_node_by_handle(&path[2..])
+            .get_node_by_handle(handle)
             .context("could not find node (by handle)")?
     } else {
         nodes
-            .get_node_by_path(&path)
+            .get_node_by_path(path)
             .context("could not find node (by path)")?
     };
 
-    if root.kind().is_file() {
+    if to_stdout {
+        if !root.kind().is_file() {
+            bail!("cannot write a folder to the standard output");
+        }
+        download_file_to_stdout(mega, root).await?;
+    } else if root.kind().is_file() {
         download_file(mega, &nodes, root, opts).await?;
     } else {
         download_folder(mega, &nodes, root, opts).await?;
@@ -109,75 +120,67 @@ pub async fn handle(_: Config, mega: &Arc<mega::Client>, opts: Opts) -> Result<E
     Ok(ExitCode::SUCCESS)
 }
 
-/// Returns whether the local file is identical to the remote file.
-async fn is_file_already_downloaded(
+/// Performs the downloading of a remote MEGA file into a local one.
+pub async fn perform_file_download(
     maybe_bar: Option<&ProgressBar>,
+    mega: &mega::Client,
     node: &mega::Node,
-    local_path: &Path,
-) -> Result<bool, Error> {
-    if !local_path.exists() {
-        return Ok(false);
-    }
-
-    if !local_path.is_file() {
-        // TODO: should we do anything fancier here ?
-        return Ok(false);
+    output_path: &Path,
+) -> Result<()> {
+    // create directories as needed before creating the file
+    if let Some(parent) = output_path.parent() {
+        fs::create_dir_all(parent).await?;
     }
 
-    let Some(remote_mac) = node.condensed_mac() else {
-        return Ok(false);
-    };
+    // the file is first downloaded into a hidden `.{name}.mega-part` file, which is only moved into place
+    // once the download is complete and its condensed MAC has been verified (by `download_node`),
+    // so that an interrupted download never leaves a truncated file behind.
+    // TODO: resume from the existing part file, once ranged downloads are exposed by the `mega` crate.
+    let part_path = part_file_path(output_path);
 
-    let file = File::open(local_path).await?;
-    let size = file.metadata().await?.len();
+    if let Err(error) = perform_part_file_download(maybe_bar, mega, node, &part_path).await {
+        let _ = fs::remove_file(&part_path).await;
+        return Err(error);
+    }
 
-    if size != node.size() {
-        return Ok(false);
+    // restore last modification date from MEGA
+    if let Some(modified_at) = node.modified_at() {
+        let mtime = FileTime::from_unix_time(
+            modified_at.timestamp(),
+            modified_at.timestamp_subsec_nanos(),
+        );
+        filetime::set_file_mtime(&part_path, mtime)
+            .context("could not restore last modification date")?
     }
 
-    let reader = match maybe_bar.cloned() {
-        Some(bar) => {
-            bar.set_style(utils::terminal::standard_progress_style());
-            bar.set_message(format!("checking `{0}`...", local_path.display()));
-
-            bar.set_position(0);
-            bar.set_length(size);
-            bar.reset();
-
-            Either::Left(file.compat().report_progress(
-                Duration::from_millis(100),
-                move |bytes_read| {
-                    bar.set_position(bytes_read as u64);
-                },
-            ))
-        }
-        None => Either::Right(file.compat()),
-    };
+    fs::rename(&part_path, output_path)
+        .await
+        .context("could not move downloaded file into place")?;
 
-    let local_mac = mega::compute_condensed_mac(
-        reader,
-        size,
-        node.aes_key(),
-        node.aes_iv().unwrap_or(&[0u8; 8]),
-    )
-    .await?;
+    Ok(())
+}
 
-    Ok(&local_mac == remote_mac)
+/// Checks whether a file name is the one of a part file used while downloading (or left behind by a killed download).
+pub fn is_part_file_name(file_name: &str) -> bool {
+    file_name.starts_with('.') && file_name.ends_with(".mega-part")
 }
 
-/// Performs the downloading of a remote MEGA file into a local one.
-async fn perform_file_download(
+/// Computes the path of the hidden part file used while downloading into the given path.
+fn part_file_path(output_path: &Path) -> PathBuf {
+    let mut file_name = OsString::from(".");
+    file_name.push(output_path.file_name().unwrap_or_default());
+    file_name.push(".mega-part");
+    output_path.with_file_name(file_name)
+}
+
+/// Downloads a single file from MEGA into the given part file.
+async fn perform_part_file_download(
     maybe_bar: Option<&ProgressBar>,
     mega: &mega::Client,
     node: &mega::Node,
-    output_path: &Path,
+    part_path: &Path,
 ) -> Result<()> {
-    // create directories as needed before creating the file
-    if let Some(parent) = output_path.parent() {
-        fs::create_dir_all(parent).await?;
-    }
-
-    let file = File::create(&output_path)
+    let file = File::create(part_path)
         .await
         .context("could not open (or create) output file")?;
 
@@ -219,16 +222,6 @@ async fn perform_file_download(
         )?;
     }
 
-    // restore last modification date from MEGA
-    if let Some(modified_at) = node.modified_at() {
-        let mtime = FileTime::from_unix_time(
-            modified_at.timestamp(),
-            modified_at.timestamp_subsec_nanos(),
-        );
-        filetime::set_file_mtime(&output_path, mtime)
-            .context("could not restore last modification date")?
-    }
-
     Ok(())
 }
 
@@ -250,8 +243,8 @@ async fn download_file(
 
     let future = || {
         let maybe_bar = maybe_bar.clone();
-        let mega = Arc::clone(&mega);
-        let nodes = Arc::clone(&nodes);
+        let mega = Arc::clone(mega);
+        let nodes = Arc::clone(nodes);
         let root_handle = Arc::clone(&root_handle);
         let output_path = Arc::clone(&output_path);
         async move {
@@ -259,7 +252,7 @@ async fn download_file(
                 .get_node_by_handle(&root_handle)
                 .context("could not get root node by handle")?;
 
-            if is_file_already_downloaded(maybe_bar.as_ref(), root, &output_path).await? {
+            if utils::nodes::is_file_identical(maybe_bar.as_ref(), root, &output_path).await? {
                 return Ok(());
             }
 
@@ -297,6 +290,31 @@ async fn download_file(
     Ok(())
 }
 
+/// Downloads a single file from MEGA, writing its contents to the standard output.
+async fn download_file_to_stdout(mega: &mega::Client, node: &mega::Node) -> Result<()> {
+    let (reader, writer) = sluice::pipe::pipe();
+
+    futures::try_join!(
+        async move {
+            mega.download_node(node, writer)
+                .await
+                .context("could not download MEGA node")
+        },
+        async move {
+            let mut stdout = tokio::io::stdout().compat_write();
+            futures::io::copy(reader, &mut stdout)
+                .await
+                .context("error during `io::copy` operation")?;
+            stdout
+                .flush()
+                .await
+                .context("could not flush the standard output")
+        },
+    )?;
+
+    Ok(())
+}
+
 #[derive(Debug, Clone, PartialEq)]
 struct InvolvedNode {
     handle: String,
@@ -324,7 +342,7 @@ async fn download_folder(
         std::iter::from_fn(|| loop {
             let node = queue.pop_front()?;
             if node.kind().is_file() {
-                let remote_path = utils::nodes::construct_relative_path(&nodes, root, node);
+                let remote_path = utils::nodes::construct_relative_path(nodes, root, node);
                 let local_path = output_path.join(&remote_path[root.name().len() + 1..]);
                 return Some(InvolvedNode {
                     handle: node.handle().to_string(),
@@ -347,7 +365,7 @@ async fn download_folder(
     let node_count =
         u64::try_from(involved_nodes.len()).context("could not cast `usize` to `u64`")?;
 
-    let maybe_multi = USER_ATTENDED.then(|| MultiProgress::new());
+    let maybe_multi = USER_ATTENDED.then(MultiProgress::new);
     let maybe_overall = maybe_multi.as_ref().map(|multi| {
         let bar = multi.add(ProgressBar::new(node_count));
         bar.set_style(utils::terminal::discrete_progress_style());
@@ -419,7 +437,7 @@ async fn download_aggregate(
                                 .get_node_by_handle(&involved_node.handle)
                                 .context("could not get node by handle")?;
 
-                            let already_downloaded = is_file_already_downloaded(
+                            let already_downloaded = utils::nodes::is_file_identical(
                                 maybe_bar.as_ref(),
                                 node,
                                 &involved_node.local_path,
@@ -527,7 +545,7 @@ pub async fn download_all_as_folder(
     let node_count =
         u64::try_from(involved_nodes.len()).context("could not cast `usize` to `u64`")?;
 
-    let maybe_multi = USER_ATTENDED.then(|| MultiProgress::new());
+    let maybe_multi = USER_ATTENDED.then(MultiProgress::new);
     let maybe_overall = maybe_multi.as_ref().map(|multi| {
         let bar = multi.add(ProgressBar::new(node_count));
         bar.set_style(utils::terminal::discrete_progress_style());
@@ -0,0 +1,247 @@
+use std::process::ExitCode;
+use std::time::Duration;
+
+use clap::Parser;
+use color_eyre::eyre::{bail, Context, ContextCompat};
+use indicatif::ProgressBar;
+
+use crate::commands::put;
+use crate::config::Config;
+use crate::utils;
+use crate::utils::terminal::USER_ATTENDED;
+use crate::Result;
+
+#[derive(Debug, Clone, PartialEq, Parser)]
+#[command(author, rename_all = "kebab-case")]
+pub struct Opts {
+    /// The password to use to decrypt the shared link, if such is used
+    #[arg(long, short)]
+    password: Option<String>,
+    /// The shared MEGA link (to a file or a folder) to import
+    link: String,
+    /// Path (eg. `/Root/folder`) or handle (eg. `H:gZlB3JxS`) to the MEGA folder to import into (the Cloud Drive, if omitted)
+    destination: Option<String>,
+}
+
+impl Opts {
+    pub fn may_need_user_session(&self) -> bool {
+        true
+    }
+}
+
+pub async fn handle(_: Config, mega: &mega::Client, opts: Opts) -> Result<ExitCode> {
+    let (public_nodes, mut own_nodes) = {
+        let maybe_bar = USER_ATTENDED.then(|| {
+            let bar = ProgressBar::new_spinner();
+            bar.set_style(utils::terminal::spinner_style());
+            bar.set_message("fetching MEGA nodes...");
+            bar.enable_steady_tick(Duration::from_millis(75));
+            bar
+        });
+
+        let nodes = futures::try_join!(
+            async {
+                match opts.password.as_deref() {
+                    None => mega
+                        .fetch_public_nodes(&opts.link)
+                        .await
+                        .context("could net fetch public MEGA nodes"),
+                    Some(password) => mega
+                        .fetch_protected_nodes(&opts.link, password)
+                        .await
+                        .context("could net fetch password-protected MEGA nodes"),
+                }
+            },
+            async {
+                mega.fetch_own_nodes()
+                    .await
+                    .context("could net fetch own MEGA nodes")
+            },
+        )?;
+
+        if let Some(bar) = maybe_bar {
+            bar.finish_and_clear();
+        }
+
+        nodes
+    };
+
+    let destination = match opts.destination.as_deref() {
+        Some(path) => {
+            if let Some(handle) = path.strip_prefix("H:") {
+                own_nodes
+                    .get_node_by_handle(handle)
+                    .context("could not find destination folder (by handle)")?
+            } else {
+                own_nodes
+                    .get_node_by_path(path.trim_end_matches('/'))
+                    .context("could not find destination folder (by path)")?
+            }
+        }
+        None => own_nodes
+            .cloud_drive()
+            .context("could not find the Cloud Drive")?,
+    };
+
+    if destination.kind().is_file() {
+        bail!("the destination must be a folder");
+    }
+
+    // TODO: import nodes server-side (re-using their keys), once it is exposed by the `mega` crate.
+    //       for now, file contents are relayed through this machine (without touching the disk).
+    let mut roots: Vec<_> = public_nodes.roots().collect();
+    roots.sort_unstable_by_key(|node| std::cmp::Reverse(node.name()));
+
+    let mut stack: Vec<_> = roots
+        .into_iter()
+        .map(|node| (node, destination.handle().to_string()))
+        .collect();
+
+    let mut has_failed = false;
+    while let Some((node, parent_handle)) = stack.pop() {
+        let parent = own_nodes
+            .get_node_by_handle(&parent_handle)
+            .context("could not find parent folder")?;
+        let parent_path = utils::nodes::construct_full_path(&own_nodes, parent);
+        let full_path = format!("{parent_path}/{0}", node.name());
+
+        if node.kind().is_file() {
+            if put::find_child(&own_nodes, parent, node.name()).is_some() {
+                has_failed = true;
+                crate::error!(
+                    to: std::io::stderr(),
+                    from: "mega:import",
+                    "could not import `{full_path}`: a node with the same name already exists"
+                )?;
+                continue;
+            }
+
+            let maybe_bar = USER_ATTENDED.then(|| {
+                let bar = ProgressBar::new(node.size());
+                bar.set_style(utils::terminal::standard_progress_style());
+                bar.set_message(format!("importing `{full_path}`..."));
+                bar
+            });
+
+            let result = import_file(maybe_bar.as_ref(), mega, node, parent).await;
+
+            if let Some(bar) = maybe_bar {
+                bar.finish_and_clear();
+            }
+
+            match result {
+                Ok(()) => {
+                    crate::success!(to: std::io::stdout(), "imported `{full_path}` !")?;
+                }
+                Err(error) => {
+                    has_failed = true;
+                    crate::error!(
+                        to: std::io::stderr(),
+                        from: "mega:import",
+                        "could not import `{full_path}`: {error:#}"
+                    )?;
+                }
+            }
+            continue;
+        }
+
+        // folders are merged into existing ones with the same name.
+        let folder_handle = match put::find_child(&own_nodes, parent, node.name()) {
+            Some(existing) if existing.kind().is_file() => {
+                has_failed = true;
+                crate::error!(
+                    to: std::io::stderr(),
+                    from: "mega:import",
+                    "could not import `{full_path}`: a file with the same name already exists"
+                )?;
+                continue;
+            }
+            Some(existing) => existing.handle().to_string(),
+            None => {
+                let result = create_folder(mega, &mut own_nodes, &parent_handle, node.name()).await;
+                match result {
+                    Ok(handle) => handle,
+                    Err(error) => {
+                        // the folder's contents are skipped along with it.
+                        has_failed = true;
+                        crate::error!(
+                            to: std::io::stderr(),
+                            from: "mega:import",
+                            "could not import `{full_path}`: {error:#}"
+                        )?;
+                        continue;
+                    }
+                }
+            }
+        };
+
+        let mut children: Vec<_> = node
+            .children()
+            .iter()
+            .filter_map(|handle| public_nodes.get_node_by_handle(handle))
+            .collect();
+        children.sort_unstable_by_key(|node| std::cmp::Reverse(node.name()));
+        stack.extend(
+            children
+                .into_iter()
+                .map(|child| (child, folder_handle.clone())),
+        );
+    }
+
+    if has_failed {
+        Ok(ExitCode::FAILURE)
+    } else {
+        Ok(ExitCode::SUCCESS)
+    }
+}
+
+/// Creates a folder within MEGA, returning its handle.
+async fn create_folder(
+    mega: &mega::Client,
+    nodes: &mut mega::Nodes,
+    parent_handle: &str,
+    folder_name: &str,
+) -> Result<String> {
+    utils::nodes::create_folder(mega, nodes, parent_handle, folder_name).await?;
+
+    let parent = nodes
+        .get_node_by_handle(parent_handle)
+        .context("could not find parent folder")?;
+    let folder = put::find_child(nodes, parent, folder_name)
+        .context("could not find newly created folder")?;
+
+    Ok(folder.handle().to_string())
+}
+
+/// Imports a single file from a shared link into a folder, by relaying its contents from the download to the upload.
+async fn import_file(
+    maybe_bar: Option<&ProgressBar>,
+    mega: &mega::Client,
+    node: &mega::Node,
+    parent: &mega::Node,
+) -> Result<()> {
+    let (reader, writer) = sluice::pipe::pipe();
+
+    let last_modified = node
+        .modified_at()
+        .map_or(mega::LastModified::Now, mega::LastModified::Set);
+
+    futures::try_join!(
+        async move {
+            mega.download_node(node, writer)
+                .await
+                .context("could not download MEGA node")
+        },
+        put::perform_upload(
+            maybe_bar,
+            mega,
+            parent,
+            node.name(),
+            reader,
+            node.size(),
+            last_modified,
+        ),
+    )?;
+
+    Ok(())
+}
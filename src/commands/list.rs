@@ -1,12 +1,16 @@
+use std::cmp::Reverse;
 use std::process::ExitCode;
 use std::time::Duration;
 
-use clap::Parser;
+use chrono::Local;
+use clap::{Parser, ValueEnum};
 use color_eyre::eyre::{Context, ContextCompat};
-use indicatif::ProgressBar;
+use indicatif::{DecimalBytes, ProgressBar};
 
 use crate::config::Config;
 use crate::utils;
+use crate::utils::nodes::NodeInfo;
+use crate::utils::output::OutputFormat;
 use crate::utils::terminal::USER_ATTENDED;
 use crate::Result;
 
@@ -16,6 +20,18 @@ pub struct Opts {
     /// Show node handles (eg. `H:gZlB3JxS`)
     #[arg(long, short = 'H')]
     show_handles: bool,
+    /// Display the nodes in a long listing format (with kinds, sizes and modification dates)
+    #[arg(long, short = 'L')]
+    long: bool,
+    /// The key by which nodes are sorted (folders are always listed before files)
+    #[arg(long, short, value_enum, default_value_t = SortKey::Name)]
+    sort: SortKey,
+    /// Reverse the sorting order
+    #[arg(long, short)]
+    reverse: bool,
+    /// The format in which to display the nodes
+    #[arg(long, short, value_enum, default_value_t = OutputFormat::Text)]
+    output: OutputFormat,
     /// The shared MEGA link from which to list nodes
     #[arg(long, short)]
     link: Option<String>,
@@ -26,6 +42,18 @@ pub struct Opts {
     path: Option<String>,
 }
 
+/// The key by which listed nodes are sorted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+#[value(rename_all = "kebab-case")]
+pub enum SortKey {
+    /// Sort by name
+    Name,
+    /// Sort by size (largest first)
+    Size,
+    /// Sort by last modification date (most recent first)
+    Mtime,
+}
+
 impl Opts {
     pub fn may_need_user_session(&self) -> bool {
         self.link.is_none() && self.password.is_none()
@@ -67,36 +95,92 @@ pub async fn handle(_: Config, mega: &mega::Client, opts: Opts) -> Result<ExitCo
         nodes
     };
 
-    if let Some(path) = opts.path {
-        let node = if path.starts_with("H:") {
+    let listed_nodes = if let Some(path) = opts.path.as_deref() {
+        let node = if let Some(handle) = path.strip_prefix("H:") {
             nodes
-                .get_node_by_handle(&path[2..])
+                .get_node_by_handle(handle)
                 .context("could not find node (by handle)")?
         } else {
             nodes
-                .get_node_by_path(&path)
+                .get_node_by_path(path)
                 .context("could not find node (by path)")?
         };
 
-        list_node(&nodes, node, opts.show_handles);
+        vec![node]
     } else {
-        let (mut folders, mut files): (Vec<_>, Vec<_>) =
-            nodes.roots().partition(|node| node.kind().is_folder());
+        sort_nodes(nodes.roots(), &opts)
+    };
 
-        folders.sort_unstable_by_key(|node| node.name());
-        files.sort_unstable_by_key(|node| node.name());
+    if opts.output.is_json() {
+        // each listed node is followed by its children, just like in the textual output.
+        let infos: Vec<_> = listed_nodes
+            .into_iter()
+            .flat_map(|node| std::iter::once(node).chain(sorted_children(&nodes, node, &opts)))
+            .map(|node| NodeInfo::new(&nodes, node))
+            .collect();
 
-        for root in folders.into_iter().chain(files) {
-            list_node(&nodes, root, opts.show_handles);
+        utils::output::print_json_items(opts.output, &infos)?;
+    } else {
+        for node in listed_nodes {
+            list_node(&nodes, node, &opts);
         }
     }
 
     Ok(ExitCode::SUCCESS)
 }
 
-fn list_node(nodes: &mega::Nodes, node: &mega::Node, show_handles: bool) {
+/// Sorts nodes according to the listing options, folders first.
+fn sort_nodes<'a>(
+    listed_nodes: impl Iterator<Item = &'a mega::Node>,
+    opts: &Opts,
+) -> Vec<&'a mega::Node> {
+    let (mut folders, mut files): (Vec<_>, Vec<_>) =
+        listed_nodes.partition(|node| node.kind().is_folder());
+
+    for group in [&mut folders, &mut files] {
+        match opts.sort {
+            SortKey::Name => group.sort_unstable_by_key(|node| node.name()),
+            SortKey::Size => group.sort_unstable_by_key(|node| (Reverse(node.size()), node.name())),
+            SortKey::Mtime => group.sort_unstable_by_key(|node| {
+                (
+                    Reverse(node.modified_at().or(node.created_at())),
+                    node.name(),
+                )
+            }),
+        }
+
+        if opts.reverse {
+            group.reverse();
+        }
+    }
+
+    folders.into_iter().chain(files).collect()
+}
+
+/// Returns the children of a node, sorted according to the listing options.
+fn sorted_children<'a>(
+    nodes: &'a mega::Nodes,
+    node: &mega::Node,
+    opts: &Opts,
+) -> Vec<&'a mega::Node> {
+    let children = node
+        .children()
+        .iter()
+        .filter_map(|hash| nodes.get_node_by_handle(hash));
+
+    sort_nodes(children, opts)
+}
+
+fn list_node(nodes: &mega::Nodes, node: &mega::Node, opts: &Opts) {
+    let show_handles = opts.show_handles;
     let full_path = utils::nodes::construct_full_path(nodes, node);
 
+    // a targeted file is displayed as a single long row, named by its full path (like `ls -l <file>` does).
+    if opts.long && node.kind().is_file() {
+        list_long(&[(node, full_path.as_str())], show_handles);
+        return;
+    }
+
     if node.kind().is_file() {
         if show_handles {
             println!("(H:{0}): {full_path}", node.handle());
@@ -111,16 +195,15 @@ fn list_node(nodes: &mega::Nodes, node: &mega::Node, show_handles: bool) {
         }
     }
 
-    let (mut folders, mut files): (Vec<_>, Vec<_>) = node
-        .children()
-        .iter()
-        .filter_map(|hash| nodes.get_node_by_handle(hash))
-        .partition(|node| node.kind().is_folder());
+    let children = sorted_children(nodes, node, opts);
 
-    folders.sort_unstable_by_key(|node| node.name());
-    files.sort_unstable_by_key(|node| node.name());
+    if opts.long {
+        let listed_nodes: Vec<_> = children.iter().map(|node| (*node, node.name())).collect();
+        list_long(&listed_nodes, show_handles);
+        return;
+    }
 
-    for node in folders.into_iter().chain(files) {
+    for node in children {
         if node.kind().is_file() {
             if show_handles {
                 println!("- (H:{0}) {1}", node.handle(), node.name());
@@ -136,3 +219,45 @@ fn list_node(nodes: &mega::Nodes, node: &mega::Node, show_handles: bool) {
         }
     }
 }
+
+/// Displays nodes (along with the name to display them by) in a long listing format, with aligned columns.
+fn list_long(listed_nodes: &[(&mega::Node, &str)], show_handles: bool) {
+    let rows: Vec<_> = listed_nodes
+        .iter()
+        .map(|&(node, name)| {
+            let kind = utils::nodes::kind_name(node.kind());
+
+            let size = if node.kind().is_file() {
+                DecimalBytes(node.size()).to_string()
+            } else {
+                String::from("-")
+            };
+
+            let modified_at = node.modified_at().or(node.created_at()).map_or_else(
+                || String::from("-"),
+                |date| {
+                    date.with_timezone(&Local)
+                        .format("%Y-%m-%d %H:%M")
+                        .to_string()
+                },
+            );
+
+            let suffix = if node.kind().is_file() { "" } else { "/" };
+            let name = if show_handles {
+                format!("(H:{0}) {name}{suffix}", node.handle())
+            } else {
+                format!("{name}{suffix}")
+            };
+
+            (kind, size, modified_at, name)
+        })
+        .collect();
+
+    let kind_width = rows.iter().map(|row| row.0.len()).max().unwrap_or_default();
+    let size_width = rows.iter().map(|row| row.1.len()).max().unwrap_or_default();
+    let date_width = rows.iter().map(|row| row.2.len()).max().unwrap_or_default();
+
+    for (kind, size, modified_at, name) in rows {
+        println!("{kind:<kind_width$}  {size:>size_width$}  {modified_at:<date_width$}  {name}");
+    }
+}
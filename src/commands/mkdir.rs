@@ -55,7 +55,7 @@ pub async fn handle(_: Config, mega: &mega::Client, opts: Opts) -> Result<ExitCo
                 .collect();
 
         if let Some((last, _)) = steps.last().copied() {
-            if last == "" {
+            if last.is_empty() {
                 steps.pop();
             }
         }
@@ -85,21 +85,7 @@ pub async fn handle(_: Config, mega: &mega::Client, opts: Opts) -> Result<ExitCo
                 bar
             });
 
-            mega.create_folder(parent_node, folder_name)
-                .await
-                .context("could not create folder within MEGA")?;
-
-            let mut is_applied = false;
-            while !is_applied {
-                let events = mega.wait_events(&nodes).await?;
-                is_applied = events.events().iter().any(|event| match event {
-                    mega::Event::NodeCreated { nodes } => nodes.iter().any(|node| {
-                        node.name() == folder_name && node.parent() == Some(parent_handle.as_str())
-                    }),
-                    _ => false,
-                });
-                nodes.apply_events(events)?;
-            }
+            utils::nodes::create_folder(mega, &mut nodes, &parent_handle, folder_name).await?;
 
             if let Some(bar) = maybe_bar {
                 bar.finish_with_message(format!("created folder `{full_path}` !"));
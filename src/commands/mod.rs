@@ -7,12 +7,20 @@ pub mod auth;
 pub mod compare;
 pub mod config;
 pub mod delete;
+pub mod df;
+pub mod du;
+pub mod find;
 pub mod follow;
 pub mod get;
+pub mod import;
 pub mod list;
 pub mod mkdir;
+pub mod mv;
 pub mod put;
 pub mod rename;
+pub mod stat;
+pub mod sync;
+pub mod trash;
 pub mod tree;
 
 use crate::config::Config;
@@ -45,6 +53,23 @@ pub enum Command {
     Follow(follow::Opts),
     /// Compare a local file with a remote node
     Compare(compare::Opts),
+    /// Synchronize a local folder with a remote MEGA folder
+    Sync(sync::Opts),
+    /// Display the disk usage of remote MEGA folders
+    Du(du::Opts),
+    /// Display the storage usage of the MEGA account
+    Df(df::Opts),
+    /// Search for remote MEGA nodes
+    Find(find::Opts),
+    /// Display the full metadata of a remote MEGA node
+    Stat(stat::Opts),
+    /// Move nodes within MEGA
+    Mv(mv::Opts),
+    /// Import a shared MEGA link into the Cloud Drive
+    Import(import::Opts),
+    /// Manage the contents of the Rubbish Bin
+    #[command(subcommand)]
+    Trash(trash::Command),
 }
 
 impl Command {
@@ -61,6 +86,14 @@ impl Command {
             Command::Delete(opts) => opts.may_need_user_session(),
             Command::Follow(opts) => opts.may_need_user_session(),
             Command::Compare(opts) => opts.may_need_user_session(),
+            Command::Sync(opts) => opts.may_need_user_session(),
+            Command::Du(opts) => opts.may_need_user_session(),
+            Command::Df(opts) => opts.may_need_user_session(),
+            Command::Find(opts) => opts.may_need_user_session(),
+            Command::Stat(opts) => opts.may_need_user_session(),
+            Command::Mv(opts) => opts.may_need_user_session(),
+            Command::Import(opts) => opts.may_need_user_session(),
+            Command::Trash(opts) => opts.may_need_user_session(),
         }
     }
 }
@@ -82,5 +115,13 @@ pub async fn handle(
         Command::Delete(opts) => delete::handle(config, mega, opts).await,
         Command::Follow(opts) => follow::handle(config, mega, opts).await,
         Command::Compare(opts) => compare::handle(config, mega, opts).await,
+        Command::Sync(opts) => sync::handle(config, mega, opts).await,
+        Command::Du(opts) => du::handle(config, mega, opts).await,
+        Command::Df(opts) => df::handle(config, mega, opts).await,
+        Command::Find(opts) => find::handle(config, mega, opts).await,
+        Command::Stat(opts) => stat::handle(config, mega, opts).await,
+        Command::Mv(opts) => mv::handle(config, mega, opts).await,
+        Command::Import(opts) => import::handle(config, mega, opts).await,
+        Command::Trash(opts) => trash::handle(config, mega, opts).await,
     }
 }
@@ -0,0 +1,203 @@
+use std::collections::HashSet;
+use std::process::ExitCode;
+use std::time::Duration;
+
+use clap::Parser;
+use color_eyre::eyre::{bail, Context, ContextCompat};
+use indicatif::ProgressBar;
+
+use crate::config::Config;
+use crate::utils;
+use crate::utils::terminal::USER_ATTENDED;
+use crate::Result;
+
+#[derive(Debug, Clone, PartialEq, Parser)]
+#[command(author, rename_all = "kebab-case")]
+pub struct Opts {
+    /// Paths (eg. `/Root/folder/file.txt`) or handles (eg. `H:gZlB3JxS`) to the MEGA nodes to move
+    #[arg(required = true)]
+    sources: Vec<String>,
+    /// Path (eg. `/Root/folder`) or handle (eg. `H:gZlB3JxS`) to the destination folder,
+    /// or the new path of the node (if a single node is moved)
+    destination: String,
+}
+
+impl Opts {
+    pub fn may_need_user_session(&self) -> bool {
+        true
+    }
+}
+
+pub async fn handle(_: Config, mega: &mega::Client, opts: Opts) -> Result<ExitCode> {
+    let nodes = {
+        let maybe_bar = USER_ATTENDED.then(|| {
+            let bar = ProgressBar::new_spinner();
+            bar.set_style(utils::terminal::spinner_style());
+            bar.set_message("fetching MEGA nodes...");
+            bar.enable_steady_tick(Duration::from_millis(75));
+            bar
+        });
+
+        let nodes = mega
+            .fetch_own_nodes()
+            .await
+            .context("could net fetch own MEGA nodes")?;
+
+        if let Some(bar) = maybe_bar {
+            bar.finish_and_clear();
+        }
+
+        nodes
+    };
+
+    let sources = opts
+        .sources
+        .iter()
+        .map(|path| {
+            if let Some(handle) = path.strip_prefix("H:") {
+                nodes
+                    .get_node_by_handle(handle)
+                    .context("could not find node (by handle)")
+            } else {
+                nodes
+                    .get_node_by_path(path)
+                    .context("could not find node (by path)")
+            }
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let maybe_destination = if let Some(handle) = opts.destination.strip_prefix("H:") {
+        let node = nodes
+            .get_node_by_handle(handle)
+            .context("could not find destination node (by handle)")?;
+        Some(node)
+    } else {
+        nodes.get_node_by_path(opts.destination.trim_end_matches('/'))
+    };
+
+    // the destination is either an existing folder to move the nodes into,
+    // or the new path of a single node (within an existing folder).
+    let (parent, maybe_new_name) = match maybe_destination {
+        Some(node) if !node.kind().is_file() => (node, None),
+        Some(_) => bail!("`{0}` already exists", opts.destination),
+        None => {
+            if sources.len() > 1 {
+                bail!("the destination must be an existing folder when moving multiple nodes");
+            }
+
+            let (parent_path, name) = opts
+                .destination
+                .trim_end_matches('/')
+                .rsplit_once('/')
+                .context("empty parent MEGA path")?;
+
+            let parent = nodes
+                .get_node_by_path(parent_path)
+                .context("could not find destination folder (by path)")?;
+            if parent.kind().is_file() {
+                bail!("`{parent_path}` is not a folder");
+            }
+
+            (parent, Some(name))
+        }
+    };
+
+    let parent_path = utils::nodes::construct_full_path(&nodes, parent);
+
+    let mut taken_names: HashSet<&str> = parent
+        .children()
+        .iter()
+        .filter_map(|handle| nodes.get_node_by_handle(handle))
+        .map(|node| node.name())
+        .collect();
+
+    let mut has_failed = false;
+    for node in sources {
+        let name = maybe_new_name.unwrap_or(node.name());
+
+        let result = check_move(&nodes, node, parent, name, &taken_names);
+        let result = match result {
+            Ok(()) => move_node(mega, node, parent, name).await,
+            Err(error) => Err(error),
+        };
+
+        match result {
+            Ok(()) => {
+                taken_names.insert(name);
+                crate::success!(
+                    to: std::io::stdout(),
+                    "moved `{0}` to `{parent_path}/{name}` !",
+                    utils::nodes::construct_full_path(&nodes, node),
+                )?;
+            }
+            Err(error) => {
+                has_failed = true;
+                crate::error!(
+                    to: std::io::stderr(),
+                    from: "mega:mv",
+                    "could not move `{0}`: {error:#}",
+                    utils::nodes::construct_full_path(&nodes, node),
+                )?;
+            }
+        }
+    }
+
+    if has_failed {
+        Ok(ExitCode::FAILURE)
+    } else {
+        Ok(ExitCode::SUCCESS)
+    }
+}
+
+/// Checks whether a node can be moved into a folder under the given name.
+fn check_move(
+    nodes: &mega::Nodes,
+    node: &mega::Node,
+    parent: &mega::Node,
+    name: &str,
+    taken_names: &HashSet<&str>,
+) -> Result<()> {
+    if !matches!(node.kind(), mega::NodeKind::File | mega::NodeKind::Folder) {
+        bail!("root nodes cannot be moved");
+    }
+
+    // refuse to move a folder into itself or one of its descendants.
+    let mut maybe_ancestor = Some(parent);
+    while let Some(ancestor) = maybe_ancestor {
+        if ancestor.handle() == node.handle() {
+            bail!("cannot move a folder into itself or one of its descendants");
+        }
+        maybe_ancestor = ancestor
+            .parent()
+            .and_then(|handle| nodes.get_node_by_handle(handle));
+    }
+
+    let is_same_place = node.parent() == Some(parent.handle()) && node.name() == name;
+    if !is_same_place && taken_names.contains(name) {
+        bail!("a node named `{name}` already exists in the destination folder");
+    }
+
+    Ok(())
+}
+
+/// Moves a node into a folder, renaming it if needed.
+async fn move_node(
+    mega: &mega::Client,
+    node: &mega::Node,
+    parent: &mega::Node,
+    name: &str,
+) -> Result<()> {
+    if node.parent() != Some(parent.handle()) {
+        mega.move_node(node, parent)
+            .await
+            .context("could not move node within MEGA")?;
+    }
+
+    if node.name() != name {
+        mega.rename_node(node, name)
+            .await
+            .context("could not rename node within MEGA")?;
+    }
+
+    Ok(())
+}
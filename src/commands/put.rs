@@ -1,38 +1,93 @@
-use std::path::PathBuf;
+use std::collections::{HashSet, VecDeque};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::pin::Pin;
 use std::process::ExitCode;
+use std::sync::Arc;
+use std::task::{self, Poll};
 use std::time::{Duration, UNIX_EPOCH};
 
-use tokio::fs::File;
+use tokio::fs::{self, File};
+use tokio::io::{AsyncSeekExt, AsyncWriteExt};
 
-use async_read_progress::TokioAsyncReadProgressExt;
+use async_read_progress::AsyncReadProgressExt;
 use chrono::{TimeZone, Utc};
-use clap::Parser;
-use color_eyre::eyre::{Context, ContextCompat};
-use indicatif::ProgressBar;
+use clap::{Parser, ValueEnum};
+use color_eyre::eyre::{bail, Context, ContextCompat};
+use futures::future::Either;
+use futures::AsyncRead;
+use indicatif::{MultiProgress, ProgressBar};
 use tokio_util::compat::TokioAsyncReadCompatExt;
 
 use crate::config::Config;
-use crate::utils;
 use crate::utils::terminal::USER_ATTENDED;
 use crate::Result;
+use crate::{utils, Error};
 
 #[derive(Debug, Clone, PartialEq, Parser)]
 #[command(author, rename_all = "kebab-case")]
 pub struct Opts {
-    /// Path of the input file
-    input_file: PathBuf,
-    /// Path (eg. `/Root/folder/file.txt) in MEGA to upload the file to
+    /// The maximum number of parallel file uploads
+    #[arg(long, short = 'P', default_value = "4", value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..))]
+    parallel: usize,
+    /// Whether to upload files even if identical ones already exist at the target path
+    /// (replacing them, unless another `--on-conflict` policy is given)
+    #[arg(long, short)]
+    force: bool,
+    /// What to do when a node with the same name already exists at the target path
+    /// (defaults to `overwrite` with `--force`, and to `fail` otherwise)
+    #[arg(long, value_enum)]
+    on_conflict: Option<ConflictPolicy>,
+    /// The size (in bytes) of the data read from the standard input, to upload it without buffering it on disk first
+    /// (the upload fails if the standard input is shorter or larger than that)
+    #[arg(long)]
+    size: Option<u64>,
+    /// Paths of the input files (or folders, for recursive uploads), or `-` to upload from the standard input
+    #[arg(required = true)]
+    inputs: Vec<PathBuf>,
+    /// Path (eg. `/Root/folder/file.txt`) in MEGA to upload to (a folder, if multiple inputs are given)
     path: String,
 }
 
+/// The policy to follow when an uploaded file's name is already taken in MEGA.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+#[value(rename_all = "kebab-case")]
+pub enum ConflictPolicy {
+    /// Leave the existing node untouched and skip the upload
+    Skip,
+    /// Move the existing file to the Rubbish Bin, once the upload succeeded
+    Overwrite,
+    /// Upload under a suffixed name (eg. `file (1).txt`)
+    Rename,
+    /// Report an error for the upload
+    Fail,
+}
+
 impl Opts {
     pub fn may_need_user_session(&self) -> bool {
         true
     }
+
+    /// Returns the conflict policy to follow, taking `--force` into account.
+    fn conflict_policy(&self) -> ConflictPolicy {
+        match self.on_conflict {
+            Some(policy) => policy,
+            None if self.force => ConflictPolicy::Overwrite,
+            None => ConflictPolicy::Fail,
+        }
+    }
+}
+
+/// The outcome of applying the conflict policy for an upload.
+struct ResolvedUpload {
+    /// The name to upload the file as.
+    file_name: String,
+    /// The handle of the existing file to move to the Rubbish Bin once the upload succeeded, if any.
+    replaced_handle: Option<String>,
 }
 
-pub async fn handle(_: Config, mega: &mega::Client, opts: Opts) -> Result<ExitCode> {
-    let nodes = {
+pub async fn handle(_: Config, mega: &Arc<mega::Client>, opts: Opts) -> Result<ExitCode> {
+    let mut nodes = {
         let maybe_bar = USER_ATTENDED.then(|| {
             let bar = ProgressBar::new_spinner();
             bar.set_style(utils::terminal::spinner_style());
@@ -55,31 +110,311 @@ pub async fn handle(_: Config, mega: &mega::Client, opts: Opts) -> Result<ExitCo
 
     // TODO: allow a folder's handle to be used as well.
 
-    let (parent_path, file_name) = opts
-        .path
-        .rsplit_once('/')
-        .context("empty parent MEGA path")?;
+    if opts.inputs.iter().any(|input| input.as_os_str() == "-") {
+        if opts.inputs.len() > 1 {
+            bail!("the standard input cannot be uploaded along with other inputs");
+        }
+
+        upload_stdin(mega, &mut nodes, &opts).await?;
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    if opts.size.is_some() {
+        bail!("`--size` can only be used when uploading from the standard input");
+    }
+
+    let targets = if let [input] = opts.inputs.as_slice() {
+        let (parent_path, name) = opts
+            .path
+            .rsplit_once('/')
+            .context("empty parent MEGA path")?;
+
+        let name = if name.is_empty() {
+            local_file_name(input).await?
+        } else {
+            name.to_string()
+        };
+
+        vec![(input.clone(), parent_path.to_string(), name)]
+    } else {
+        let parent_path = opts.path.trim_end_matches('/');
+
+        let mut targets = Vec::with_capacity(opts.inputs.len());
+        for input in opts.inputs.iter() {
+            let name = local_file_name(input).await?;
+            targets.push((input.clone(), parent_path.to_string(), name));
+        }
+
+        targets
+    };
+
+    let mut involved_folders = Vec::default();
+    let mut involved_files = Vec::default();
+    for (input, parent_path, name) in targets.iter() {
+        if input.is_dir() {
+            collect_local_folder(
+                input,
+                parent_path,
+                name,
+                &mut involved_folders,
+                &mut involved_files,
+            )
+            .await?;
+        } else {
+            involved_files.push(InvolvedFile {
+                local_path: input.clone(),
+                parent_path: parent_path.clone(),
+                file_name: name.clone(),
+            });
+        }
+    }
+
+    // conflicts are resolved against the nodes fetched beforehand, so two inputs
+    // targeting the same remote file would end up uploaded under the same name.
+    let mut remote_targets = HashSet::with_capacity(involved_files.len());
+    for involved_file in involved_files.iter() {
+        let remote_target = (&involved_file.parent_path, &involved_file.file_name);
+        if !remote_targets.insert(remote_target) {
+            bail!(
+                "several inputs would be uploaded to `{0}/{1}`",
+                involved_file.parent_path,
+                involved_file.file_name,
+            );
+        }
+    }
+
+    // create the missing ancestors of the target path, like `mkdir --parents` would.
+    let mut ancestor_folders: Vec<_> = targets
+        .iter()
+        .flat_map(|(_, parent_path, _)| remote_ancestors(parent_path))
+        .collect();
+    ancestor_folders.dedup();
+    create_remote_folders(mega, &mut nodes, ancestor_folders).await?;
 
-    let file_name = if file_name.is_empty() {
-        opts.input_file
+    if let [(input, parent_path, name)] = targets.as_slice() {
+        if !input.is_dir() {
+            upload_file(mega, &nodes, parent_path, name, input, &opts).await?;
+            return Ok(ExitCode::SUCCESS);
+        }
+    }
+
+    create_remote_folders(mega, &mut nodes, involved_folders).await?;
+
+    let nodes = Arc::new(nodes);
+
+    let file_count =
+        u64::try_from(involved_files.len()).context("could not cast `usize` to `u64`")?;
+
+    let maybe_multi = USER_ATTENDED.then(MultiProgress::new);
+    let maybe_overall = maybe_multi.as_ref().map(|multi| {
+        let bar = multi.add(ProgressBar::new(file_count));
+        bar.set_style(utils::terminal::discrete_progress_style());
+        bar.set_message(format!("uploading files into `{0}`...", opts.path));
+        bar
+    });
+
+    let (skipped_count, errors) = upload_aggregate(
+        &opts,
+        mega,
+        &nodes,
+        involved_files,
+        maybe_overall.clone(),
+        maybe_multi.clone(),
+    )
+    .await?;
+
+    if let Some(multi) = maybe_multi.as_ref() {
+        multi.clear()?;
+    }
+
+    if !errors.is_empty() {
+        for (involved_file, error) in errors.iter() {
+            crate::error!(
+                to: std::io::stderr(),
+                from: "mega:put",
+                "could not upload `{0}`: {1:#}",
+                involved_file.local_path.display(),
+                error,
+            )?;
+        }
+
+        crate::error!(
+            to: std::io::stderr(),
+            from: "mega:put",
+            "{0} out of {1} file uploads failed",
+            errors.len(),
+            file_count,
+        )?;
+
+        return Ok(ExitCode::FAILURE);
+    }
+
+    crate::success!(
+        to: std::io::stdout(),
+        "uploaded {0} files into `{1}` !",
+        file_count - skipped_count,
+        opts.path,
+    )?;
+
+    if skipped_count > 0 {
+        crate::info!(
+            to: std::io::stdout(),
+            "skipped {skipped_count} files (already uploaded, or skipped due to `--on-conflict`)"
+        )?;
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
+
+/// Returns the `(parent, folder_name)` pairs of every folder along a remote path (excluding its root).
+fn remote_ancestors(path: &str) -> Vec<(String, String)> {
+    let mut components = path.split('/').filter(|component| !component.is_empty());
+
+    let Some(root) = components.next() else {
+        return Vec::default();
+    };
+
+    let mut parent = format!("/{root}");
+    components
+        .map(|component| {
+            let pair = (parent.clone(), component.to_string());
+            parent = format!("{parent}/{component}");
+            pair
+        })
+        .collect()
+}
+
+/// Returns the name to use in MEGA for a local file or folder.
+async fn local_file_name(input_path: &Path) -> Result<String> {
+    let file_name = match input_path.file_name() {
+        Some(file_name) => file_name.to_os_string(),
+        None => fs::canonicalize(input_path)
+            .await
+            .context("could not resolve input path")?
             .file_name()
             .context("could not get output file name")?
-            .to_str()
-            .context("file name is not valid UTF-8")?
-    } else {
-        file_name
+            .to_os_string(),
     };
 
-    let full_path = format!("{parent_path}/{file_name}");
+    file_name
+        .into_string()
+        .ok()
+        .context("file name is not valid UTF-8")
+}
 
-    let parent_node = nodes
-        .get_node_by_path(parent_path)
-        .context("could not find parent node (by path)")?;
+/// Returns whether the local file is identical to the remote file at the given path, if any.
+async fn is_file_already_uploaded(
+    maybe_bar: Option<&ProgressBar>,
+    nodes: &mega::Nodes,
+    remote_path: &str,
+    local_path: &Path,
+) -> Result<bool> {
+    let Some(node) = nodes.get_node_by_path(remote_path) else {
+        return Ok(false);
+    };
+
+    if !node.kind().is_file() {
+        return Ok(false);
+    }
+
+    utils::nodes::is_file_identical(maybe_bar, node, local_path).await
+}
+
+/// Finds the child node with the given name within a folder, if any.
+pub fn find_child<'a>(
+    nodes: &'a mega::Nodes,
+    parent_node: &mega::Node,
+    name: &str,
+) -> Option<&'a mega::Node> {
+    parent_node
+        .children()
+        .iter()
+        .filter_map(|handle| nodes.get_node_by_handle(handle))
+        .find(|node| node.name() == name)
+}
+
+/// Applies the conflict policy for an upload (or returns `None` to skip it).
+fn resolve_conflict(
+    nodes: &mega::Nodes,
+    parent_node: &mega::Node,
+    file_name: &str,
+    policy: ConflictPolicy,
+) -> Result<Option<ResolvedUpload>> {
+    let Some(existing) = find_child(nodes, parent_node, file_name) else {
+        return Ok(Some(ResolvedUpload {
+            file_name: file_name.to_string(),
+            replaced_handle: None,
+        }));
+    };
+
+    match policy {
+        ConflictPolicy::Skip => Ok(None),
+        ConflictPolicy::Fail => {
+            bail!("a node named `{file_name}` already exists in the target folder")
+        }
+        ConflictPolicy::Overwrite => {
+            if !existing.kind().is_file() {
+                bail!("a folder named `{file_name}` already exists in the target folder");
+            }
+
+            Ok(Some(ResolvedUpload {
+                file_name: file_name.to_string(),
+                replaced_handle: Some(existing.handle().to_string()),
+            }))
+        }
+        ConflictPolicy::Rename => {
+            let (stem, maybe_extension) = match file_name.rsplit_once('.') {
+                Some((stem, extension)) if !stem.is_empty() => (stem, Some(extension)),
+                _ => (file_name, None),
+            };
 
-    let file = File::open(&opts.input_file)
+            let name = (1u64..)
+                .map(|index| match maybe_extension {
+                    Some(extension) => format!("{stem} ({index}).{extension}"),
+                    None => format!("{stem} ({index})"),
+                })
+                .find(|name| find_child(nodes, parent_node, name).is_none())
+                .context("could not find an available name for the file")?;
+
+            Ok(Some(ResolvedUpload {
+                file_name: name,
+                replaced_handle: None,
+            }))
+        }
+    }
+}
+
+/// Moves the file replaced by an upload to the Rubbish Bin.
+pub async fn trash_replaced_node(
+    mega: &mega::Client,
+    nodes: &mega::Nodes,
+    handle: &str,
+) -> Result<()> {
+    let node = nodes
+        .get_node_by_handle(handle)
+        .context("could not find replaced node")?;
+    let rubbish_bin = nodes.rubbish_bin().context("could not find Rubbish Bin")?;
+
+    mega.move_node(node, rubbish_bin)
+        .await
+        .context("could not move replaced node to the Rubbish Bin")
+}
+
+/// Performs the uploading of a local file into a remote MEGA folder.
+// TODO: support resuming interrupted uploads (by persisting the upload URL and the offsets of the chunks already sent),
+//       once `upload_node` exposes them in the `mega` crate.
+pub async fn perform_file_upload(
+    maybe_bar: Option<&ProgressBar>,
+    mega: &mega::Client,
+    parent_node: &mega::Node,
+    file_name: &str,
+    input_path: &Path,
+) -> Result<()> {
+    let file = File::open(input_path)
         .await
-        .context("could not open output file")?;
+        .context("could not open input file")?;
     let metadata = file.metadata().await?;
+    let size = metadata.len();
 
     let last_modified = {
         let date = metadata
@@ -99,68 +434,608 @@ pub async fn handle(_: Config, mega: &mega::Client, opts: Opts) -> Result<ExitCo
         mega::LastModified::Set(date)
     };
 
-    let (reader, mut writer) = sluice::pipe::pipe();
+    perform_upload(
+        maybe_bar,
+        mega,
+        parent_node,
+        file_name,
+        file.compat(),
+        size,
+        last_modified,
+    )
+    .await
+}
+
+/// Performs the uploading of the contents of a reader into a remote MEGA folder.
+pub async fn perform_upload<R: AsyncRead + Unpin>(
+    maybe_bar: Option<&ProgressBar>,
+    mega: &mega::Client,
+    parent_node: &mega::Node,
+    file_name: &str,
+    reader: R,
+    size: u64,
+    last_modified: mega::LastModified,
+) -> Result<()> {
+    let (pipe_reader, mut pipe_writer) = sluice::pipe::pipe();
+
+    let reader = match maybe_bar.cloned() {
+        Some(bar) => {
+            bar.set_position(0);
+            bar.set_length(size);
+            bar.reset();
+
+            Either::Left(
+                reader.report_progress(Duration::from_millis(100), move |bytes_read| {
+                    bar.set_position(bytes_read as u64);
+                }),
+            )
+        }
+        None => Either::Right(reader),
+    };
+
+    futures::try_join!(
+        async move {
+            mega.upload_node(parent_node, file_name, size, pipe_reader, last_modified)
+                .await
+                .context("could not upload MEGA node")
+        },
+        async move {
+            futures::io::copy(reader, &mut pipe_writer)
+                .await
+                .context("error during `io::copy` operation")
+        },
+    )?;
+
+    Ok(())
+}
+
+/// The amount of trailing bytes buffered by `ExactSizeReader`, to check that the input ends right after them.
+const EXACT_SIZE_TAIL_LENGTH: u64 = 64 * 1024;
+
+/// A reader that fails unless its inner reader yields exactly the expected amount of bytes.
+///
+/// The last bytes are held back until the end of the inner reader is reached,
+/// so that an input larger than expected is reported before the upload can complete.
+struct ExactSizeReader<R> {
+    inner: R,
+    remaining: u64,
+    tail: Vec<u8>,
+    tail_offset: usize,
+    is_tail_complete: bool,
+}
+
+impl<R> ExactSizeReader<R> {
+    fn new(inner: R, size: u64) -> Self {
+        Self {
+            inner,
+            remaining: size,
+            tail: Vec::default(),
+            tail_offset: 0,
+            is_tail_complete: false,
+        }
+    }
+}
+
+impl<R: AsyncRead + Unpin> AsyncRead for ExactSizeReader<R> {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut task::Context<'_>,
+        buf: &mut [u8],
+    ) -> Poll<io::Result<usize>> {
+        let this = &mut *self;
+
+        let too_short = || {
+            io::Error::new(
+                io::ErrorKind::UnexpectedEof,
+                "the standard input is shorter than `--size`",
+            )
+        };
+
+        if this.remaining > EXACT_SIZE_TAIL_LENGTH {
+            let max = usize::try_from(this.remaining - EXACT_SIZE_TAIL_LENGTH)
+                .unwrap_or(usize::MAX)
+                .min(buf.len());
+            let read = futures::ready!(Pin::new(&mut this.inner).poll_read(cx, &mut buf[..max]))?;
+            if read == 0 && max > 0 {
+                return Poll::Ready(Err(too_short()));
+            }
+            this.remaining -= read as u64;
+            return Poll::Ready(Ok(read));
+        }
+
+        // `remaining` is at most `EXACT_SIZE_TAIL_LENGTH` here, so this can't truncate.
+        let expected = this.remaining as usize;
+        while !this.is_tail_complete {
+            // one extra byte is requested, to detect inputs larger than expected.
+            let start = this.tail.len();
+            this.tail.resize(expected + 1, 0);
+            let result = Pin::new(&mut this.inner).poll_read(cx, &mut this.tail[start..]);
+            let read = match result {
+                Poll::Ready(Ok(read)) => read,
+                Poll::Ready(Err(error)) => {
+                    this.tail.truncate(start);
+                    return Poll::Ready(Err(error));
+                }
+                Poll::Pending => {
+                    this.tail.truncate(start);
+                    return Poll::Pending;
+                }
+            };
+            this.tail.truncate(start + read);
+
+            if read == 0 {
+                if this.tail.len() < expected {
+                    return Poll::Ready(Err(too_short()));
+                }
+                this.is_tail_complete = true;
+            } else if this.tail.len() > expected {
+                return Poll::Ready(Err(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    "the standard input is larger than `--size`",
+                )));
+            }
+        }
+
+        let available = &this.tail[this.tail_offset..];
+        let count = available.len().min(buf.len());
+        buf[..count].copy_from_slice(&available[..count]);
+        this.tail_offset += count;
+
+        Poll::Ready(Ok(count))
+    }
+}
+
+/// Uploads the contents of the standard input to MEGA, with progress reporting.
+async fn upload_stdin(mega: &mega::Client, nodes: &mut mega::Nodes, opts: &Opts) -> Result<()> {
+    let (parent_path, file_name) = opts
+        .path
+        .rsplit_once('/')
+        .context("empty parent MEGA path")?;
+
+    if file_name.is_empty() {
+        bail!("the MEGA path must end with a file name when uploading from the standard input");
+    }
+
+    // create the missing ancestors of the target path, like `mkdir --parents` would.
+    create_remote_folders(mega, nodes, remote_ancestors(parent_path)).await?;
+    let nodes = &*nodes;
+
+    let parent_node = nodes
+        .get_node_by_path(parent_path)
+        .context("could not find parent node (by path)")?;
+
+    // the conflict is resolved before reading the standard input,
+    // to avoid needlessly spilling it when the upload ends up failing or skipped.
+    let maybe_resolved = resolve_conflict(nodes, parent_node, file_name, opts.conflict_policy())?;
+
+    let full_path = format!("{parent_path}/{file_name}");
+
+    let Some(resolved) = maybe_resolved else {
+        crate::warning!(
+            to: std::io::stderr(),
+            from: "mega:put",
+            "`{full_path}` already exists, skipping"
+        )?;
+        return Ok(());
+    };
+
+    let (reader, size) = match opts.size {
+        Some(size) => (
+            Either::Left(ExactSizeReader::new(tokio::io::stdin().compat(), size)),
+            size,
+        ),
+        None => {
+            // the size of the uploaded file must be known upfront,
+            // so the standard input is first spilled into a temporary file.
+            let mut spill_file = File::from_std(
+                tempfile::tempfile().context("could not create temporary spill file")?,
+            );
+
+            tokio::io::copy(&mut tokio::io::stdin(), &mut spill_file)
+                .await
+                .context("could not read from the standard input")?;
+            spill_file.flush().await?;
+            spill_file.rewind().await?;
+
+            let size = spill_file.metadata().await?.len();
+            (Either::Right(spill_file.compat()), size)
+        }
+    };
+
+    let full_path = format!("{parent_path}/{0}", resolved.file_name);
+
+    let maybe_bar = USER_ATTENDED.then(|| {
+        let bar = ProgressBar::new(size);
+        bar.set_style(utils::terminal::standard_progress_style());
+        bar.set_message(format!("uploading standard input into `{full_path}`..."));
+        bar
+    });
+
+    perform_upload(
+        maybe_bar.as_ref(),
+        mega,
+        parent_node,
+        &resolved.file_name,
+        reader,
+        size,
+        mega::LastModified::Now,
+    )
+    .await?;
+
+    if let Some(handle) = resolved.replaced_handle.as_deref() {
+        trash_replaced_node(mega, nodes, handle).await?;
+    }
+
+    if let Some(bar) = maybe_bar {
+        bar.finish_with_message(format!("standard input uploaded into `{full_path}` !"));
+    }
+
+    Ok(())
+}
+
+/// Uploads a single file to MEGA, with progress reporting.
+async fn upload_file(
+    mega: &mega::Client,
+    nodes: &mega::Nodes,
+    parent_path: &str,
+    file_name: &str,
+    input_path: &Path,
+    opts: &Opts,
+) -> Result<()> {
+    let full_path = format!("{parent_path}/{file_name}");
+
+    let parent_node = nodes
+        .get_node_by_path(parent_path)
+        .context("could not find parent node (by path)")?;
+
+    let maybe_bar = USER_ATTENDED.then(|| ProgressBar::new(0));
+
+    if !opts.force
+        && is_file_already_uploaded(maybe_bar.as_ref(), nodes, &full_path, input_path).await?
+    {
+        if let Some(bar) = maybe_bar {
+            bar.finish_with_message(format!(
+                "`{0}` already uploaded into `{1}`, skipping !",
+                input_path.display(),
+                full_path,
+            ));
+        }
+        return Ok(());
+    }
+
+    let maybe_resolved = resolve_conflict(nodes, parent_node, file_name, opts.conflict_policy())?;
+
+    let Some(resolved) = maybe_resolved else {
+        if let Some(bar) = maybe_bar {
+            bar.finish_with_message(format!("`{full_path}` already exists, skipping !"));
+        }
+        return Ok(());
+    };
+
+    let full_path = format!("{parent_path}/{0}", resolved.file_name);
 
-    if *USER_ATTENDED {
-        let bar = ProgressBar::new(metadata.len());
+    if let Some(bar) = maybe_bar.as_ref() {
         bar.set_style(utils::terminal::standard_progress_style());
         bar.set_message(format!(
             "uploading `{0}` into `{1}`...",
-            opts.input_file.display(),
+            input_path.display(),
             full_path,
         ));
+    }
 
-        let file = {
-            let bar = bar.clone();
-            file.report_progress(Duration::from_millis(100), move |bytes_read| {
-                bar.set_position(bytes_read as u64);
-            })
-        };
+    perform_file_upload(
+        maybe_bar.as_ref(),
+        mega,
+        parent_node,
+        &resolved.file_name,
+        input_path,
+    )
+    .await?;
 
-        futures::try_join!(
-            async move {
-                mega.upload_node(
-                    parent_node,
-                    file_name,
-                    metadata.len(),
-                    reader,
-                    last_modified,
-                )
-                .await
-                .context("could not upload MEGA node")
-            },
-            async move {
-                futures::io::copy(file.compat(), &mut writer)
-                    .await
-                    .context("error during `io::copy` operation")
-            },
-        )?;
+    if let Some(handle) = resolved.replaced_handle.as_deref() {
+        trash_replaced_node(mega, nodes, handle).await?;
+    }
 
+    if let Some(bar) = maybe_bar {
         bar.finish_with_message(format!(
             "`{0}` uploaded into `{1}` !",
-            opts.input_file.display(),
+            input_path.display(),
             full_path,
         ));
-    } else {
-        futures::try_join!(
-            async move {
-                mega.upload_node(
-                    parent_node,
-                    file_name,
-                    metadata.len(),
-                    reader,
-                    last_modified,
-                )
-                .await
-                .context("could not upload MEGA node")
-            },
-            async move {
-                futures::io::copy(file.compat(), &mut writer)
-                    .await
-                    .context("error during `io::copy` operation")
-            },
-        )?;
     }
 
-    Ok(ExitCode::SUCCESS)
+    Ok(())
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct InvolvedFile {
+    local_path: PathBuf,
+    parent_path: String,
+    file_name: String,
+}
+
+/// Recursively collects the folders and files to upload from a local folder.
+async fn collect_local_folder(
+    input_path: &Path,
+    parent_path: &str,
+    folder_name: &str,
+    involved_folders: &mut Vec<(String, String)>,
+    involved_files: &mut Vec<InvolvedFile>,
+) -> Result<()> {
+    let root_path = format!("{parent_path}/{folder_name}");
+    involved_folders.push((parent_path.to_string(), folder_name.to_string()));
+
+    let mut queue = VecDeque::default();
+    queue.push_back((input_path.to_path_buf(), root_path));
+
+    while let Some((local_path, remote_path)) = queue.pop_front() {
+        let mut entries = Vec::default();
+        let mut read_dir = fs::read_dir(&local_path)
+            .await
+            .context("could not read local folder")?;
+        while let Some(entry) = read_dir.next_entry().await? {
+            entries.push(entry);
+        }
+        entries.sort_unstable_by_key(|entry| entry.file_name());
+
+        for entry in entries {
+            let name = entry
+                .file_name()
+                .into_string()
+                .ok()
+                .context("file name is not valid UTF-8")?;
+
+            // TODO: should symbolic links be followed ?
+            let file_type = entry.file_type().await?;
+            if file_type.is_dir() {
+                involved_folders.push((remote_path.clone(), name.clone()));
+                queue.push_back((entry.path(), format!("{remote_path}/{name}")));
+            } else if file_type.is_file() {
+                involved_files.push(InvolvedFile {
+                    local_path: entry.path(),
+                    parent_path: remote_path.clone(),
+                    file_name: name,
+                });
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Creates the missing remote folders, applying their creation events as we go.
+pub async fn create_remote_folders(
+    mega: &mega::Client,
+    nodes: &mut mega::Nodes,
+    involved_folders: Vec<(String, String)>,
+) -> Result<()> {
+    for (parent, folder_name) in involved_folders {
+        let full_path = format!("{parent}/{folder_name}");
+        if let Some(node) = nodes.get_node_by_path(&full_path) {
+            if node.kind().is_file() {
+                bail!("a file already exists at `{full_path}`");
+            }
+            continue;
+        }
+
+        let parent_handle = nodes
+            .get_node_by_path(&parent)
+            .context("could not find parent folder")?
+            .handle()
+            .to_string();
+
+        let maybe_bar = USER_ATTENDED.then(|| {
+            let bar = ProgressBar::new_spinner();
+            bar.set_style(utils::terminal::spinner_style());
+            bar.set_message(format!("creating folder `{full_path}`..."));
+            bar.enable_steady_tick(Duration::from_millis(75));
+            bar
+        });
+
+        utils::nodes::create_folder(mega, nodes, &parent_handle, &folder_name).await?;
+
+        if let Some(bar) = maybe_bar {
+            bar.finish_and_clear();
+        }
+    }
+
+    Ok(())
+}
+
+/// Uploads multiple files to MEGA in parallel, with progress reporting.
+///
+/// Errors are collected per file, instead of aborting the other uploads.
+/// Returns the number of skipped files, along with the collected errors.
+async fn upload_aggregate(
+    opts: &Opts,
+    mega: &Arc<mega::Client>,
+    nodes: &Arc<mega::Nodes>,
+    involved_files: Vec<InvolvedFile>,
+    maybe_overall: Option<ProgressBar>,
+    maybe_multi: Option<MultiProgress>,
+) -> Result<(u64, Vec<(InvolvedFile, Error)>)> {
+    let (tx, rx) = async_channel::bounded::<InvolvedFile>(opts.parallel);
+
+    let tasks: Vec<_> = (0..opts.parallel)
+        .map(|_| {
+            let opts = opts.clone();
+            let maybe_multi = maybe_multi.clone();
+            let maybe_overall = maybe_overall.clone();
+            let mega = Arc::clone(mega);
+            let nodes = Arc::clone(nodes);
+            let rx = rx.clone();
+            tokio::spawn(async move {
+                let mut skipped_count = 0;
+                let mut errors = Vec::default();
+
+                while let Ok(involved_file) = rx.recv().await {
+                    let maybe_bar = maybe_multi
+                        .as_ref()
+                        .map(|multi| multi.add(ProgressBar::new(0)));
+
+                    let result = async {
+                        let parent_node = nodes
+                            .get_node_by_path(&involved_file.parent_path)
+                            .context("could not find parent folder")?;
+
+                        let remote_path = format!(
+                            "{0}/{1}",
+                            involved_file.parent_path, involved_file.file_name,
+                        );
+
+                        let already_uploaded = !opts.force
+                            && is_file_already_uploaded(
+                                maybe_bar.as_ref(),
+                                &nodes,
+                                &remote_path,
+                                &involved_file.local_path,
+                            )
+                            .await?;
+
+                        if already_uploaded {
+                            return Ok(false);
+                        }
+
+                        let maybe_resolved = resolve_conflict(
+                            &nodes,
+                            parent_node,
+                            &involved_file.file_name,
+                            opts.conflict_policy(),
+                        )?;
+
+                        let Some(resolved) = maybe_resolved else {
+                            return Ok(false);
+                        };
+
+                        if let Some(bar) = maybe_bar.as_ref() {
+                            bar.set_style(utils::terminal::standard_progress_style());
+                            bar.set_message(format!(
+                                "uploading `{0}`...",
+                                involved_file.local_path.display(),
+                            ));
+                        }
+
+                        perform_file_upload(
+                            maybe_bar.as_ref(),
+                            &mega,
+                            parent_node,
+                            &resolved.file_name,
+                            &involved_file.local_path,
+                        )
+                        .await?;
+
+                        if let Some(handle) = resolved.replaced_handle.as_deref() {
+                            trash_replaced_node(&mega, &nodes, handle).await?;
+                        }
+
+                        Ok::<_, Error>(true)
+                    }
+                    .await;
+
+                    match result {
+                        Ok(true) => {}
+                        Ok(false) => skipped_count += 1,
+                        Err(error) => errors.push((involved_file, error)),
+                    }
+
+                    if let (Some(multi), Some(bar)) = (maybe_multi.as_ref(), maybe_bar) {
+                        multi.remove(&bar);
+                    }
+                    if let Some(overall) = maybe_overall.as_ref() {
+                        overall.inc(1);
+                    }
+                }
+
+                (skipped_count, errors)
+            })
+        })
+        .collect();
+
+    drop(rx);
+    for involved_file in involved_files {
+        tx.send(involved_file).await?;
+    }
+    drop(tx);
+
+    let mut skipped_count = 0;
+    let mut errors = Vec::default();
+    for task in tasks {
+        let (task_skipped_count, task_errors) = task.await?;
+        skipped_count += task_skipped_count;
+        errors.extend(task_errors);
+    }
+
+    Ok((skipped_count, errors))
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::io::Cursor;
+    use futures::AsyncReadExt;
+
+    use super::*;
+
+    /// Reads the whole input through an [`ExactSizeReader`] expecting `size` bytes.
+    fn read_exact_size(input: &[u8], size: u64) -> io::Result<Vec<u8>> {
+        let mut reader = ExactSizeReader::new(Cursor::new(input), size);
+        let mut output = Vec::default();
+        futures::executor::block_on(reader.read_to_end(&mut output))?;
+        Ok(output)
+    }
+
+    /// Generates an input of the given length, with non-uniform contents.
+    fn input_of_length(length: u64) -> Vec<u8> {
+        (0..length).map(|index| (index % 251) as u8).collect()
+    }
+
+    #[test]
+    fn reads_inputs_of_exact_size() {
+        let sizes = [
+            0,
+            1,
+            EXACT_SIZE_TAIL_LENGTH - 1,
+            EXACT_SIZE_TAIL_LENGTH,
+            EXACT_SIZE_TAIL_LENGTH + 1,
+            3 * EXACT_SIZE_TAIL_LENGTH + 7,
+        ];
+
+        for size in sizes {
+            let input = input_of_length(size);
+            let output = read_exact_size(&input, size).unwrap();
+            assert_eq!(output, input, "size: {size}");
+        }
+    }
+
+    #[test]
+    fn rejects_shorter_inputs() {
+        let sizes = [
+            1,
+            EXACT_SIZE_TAIL_LENGTH,
+            EXACT_SIZE_TAIL_LENGTH + 1,
+            3 * EXACT_SIZE_TAIL_LENGTH + 7,
+        ];
+
+        for size in sizes {
+            let input = input_of_length(size - 1);
+            let error = read_exact_size(&input, size).unwrap_err();
+            assert_eq!(error.kind(), io::ErrorKind::UnexpectedEof, "size: {size}");
+        }
+    }
+
+    #[test]
+    fn rejects_larger_inputs() {
+        let sizes = [
+            0,
+            EXACT_SIZE_TAIL_LENGTH - 1,
+            EXACT_SIZE_TAIL_LENGTH,
+            EXACT_SIZE_TAIL_LENGTH + 1,
+            3 * EXACT_SIZE_TAIL_LENGTH + 7,
+        ];
+
+        for size in sizes {
+            let input = input_of_length(size + 1);
+            let error = read_exact_size(&input, size).unwrap_err();
+            assert_eq!(error.kind(), io::ErrorKind::InvalidData, "size: {size}");
+        }
+    }
 }
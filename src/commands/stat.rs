@@ -0,0 +1,132 @@
+use std::process::ExitCode;
+use std::time::Duration;
+
+use clap::Parser;
+use color_eyre::eyre::{Context, ContextCompat};
+use indicatif::ProgressBar;
+use serde::Serialize;
+
+use crate::config::Config;
+use crate::utils;
+use crate::utils::nodes::NodeInfo;
+use crate::utils::output::OutputFormat;
+use crate::utils::terminal::USER_ATTENDED;
+use crate::Result;
+
+#[derive(Debug, Clone, PartialEq, Parser)]
+#[command(author, rename_all = "kebab-case")]
+pub struct Opts {
+    /// The format in which to display the node's metadata
+    #[arg(long, short, value_enum, default_value_t = OutputFormat::Text)]
+    output: OutputFormat,
+    /// Path (eg. `/Root/folder/file.txt`) or handle (eg. `H:gZlB3JxS`) to the MEGA node to inspect
+    path: String,
+}
+
+impl Opts {
+    pub fn may_need_user_session(&self) -> bool {
+        true
+    }
+}
+
+/// The full metadata of a node.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct NodeStat {
+    #[serde(flatten)]
+    info: NodeInfo,
+    /// The condensed MAC of the node (as hexadecimal), if it is a file.
+    condensed_mac: Option<String>,
+    /// Whether the node has a thumbnail.
+    has_thumbnail: bool,
+    /// Whether the node has a preview image.
+    has_preview_image: bool,
+    /// The number of direct child files, if it is a folder.
+    child_files: Option<usize>,
+    /// The number of direct child folders, if it is a folder.
+    child_folders: Option<usize>,
+}
+
+pub async fn handle(_: Config, mega: &mega::Client, opts: Opts) -> Result<ExitCode> {
+    let nodes = {
+        let maybe_bar = USER_ATTENDED.then(|| {
+            let bar = ProgressBar::new_spinner();
+            bar.set_style(utils::terminal::spinner_style());
+            bar.set_message("fetching MEGA nodes...");
+            bar.enable_steady_tick(Duration::from_millis(75));
+            bar
+        });
+
+        let nodes = mega
+            .fetch_own_nodes()
+            .await
+            .context("could net fetch own MEGA nodes")?;
+
+        if let Some(bar) = maybe_bar {
+            bar.finish_and_clear();
+        }
+
+        nodes
+    };
+
+    let node = if let Some(handle) = opts.path.strip_prefix("H:") {
+        nodes
+            .get_node_by_handle(handle)
+            .context("could not find node (by handle)")?
+    } else {
+        nodes
+            .get_node_by_path(&opts.path)
+            .context("could not find node (by path)")?
+    };
+
+    let (child_files, child_folders) = if node.kind().is_file() {
+        (None, None)
+    } else {
+        let (files, folders): (Vec<_>, Vec<_>) = node
+            .children()
+            .iter()
+            .filter_map(|handle| nodes.get_node_by_handle(handle))
+            .partition(|child| child.kind().is_file());
+        (Some(files.len()), Some(folders.len()))
+    };
+
+    let stat = NodeStat {
+        info: NodeInfo::new(&nodes, node),
+        condensed_mac: utils::nodes::condensed_mac_hex(node),
+        has_thumbnail: node.has_thumbnail(),
+        has_preview_image: node.has_preview_image(),
+        child_files,
+        child_folders,
+    };
+
+    if opts.output.is_json() {
+        utils::output::print_json_item(opts.output, &stat)?;
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    let info = &stat.info;
+    crate::info!(to: std::io::stdout(), "handle = `H:{0}`", info.handle)?;
+    crate::info!(to: std::io::stdout(), "name = `{0}`", info.name)?;
+    crate::info!(to: std::io::stdout(), "path = `{0}`", info.path)?;
+    crate::info!(to: std::io::stdout(), "kind = `{0}`", info.kind)?;
+    crate::info!(to: std::io::stdout(), "size = `{0}`", info.size)?;
+    if let Some(parent) = info.parent.as_deref() {
+        crate::info!(to: std::io::stdout(), "parent = `H:{parent}`")?;
+    }
+    if let Some(created_at) = info.created_at.as_deref() {
+        crate::info!(to: std::io::stdout(), "created_at = `{created_at}`")?;
+    }
+    if let Some(modified_at) = info.modified_at.as_deref() {
+        crate::info!(to: std::io::stdout(), "modified_at = `{modified_at}`")?;
+    }
+    if let Some(condensed_mac) = stat.condensed_mac.as_deref() {
+        crate::info!(to: std::io::stdout(), "condensed_mac = `{condensed_mac}`")?;
+    }
+    crate::info!(to: std::io::stdout(), "has_thumbnail = `{0}`", stat.has_thumbnail)?;
+    crate::info!(to: std::io::stdout(), "has_preview_image = `{0}`", stat.has_preview_image)?;
+    if let (Some(child_files), Some(child_folders)) = (stat.child_files, stat.child_folders) {
+        crate::info!(to: std::io::stdout(), "child_files = `{child_files}`")?;
+        crate::info!(to: std::io::stdout(), "child_folders = `{child_folders}`")?;
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
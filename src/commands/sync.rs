@@ -0,0 +1,1268 @@
+use std::collections::{BTreeMap, BTreeSet, VecDeque};
+use std::fmt;
+use std::fs::Metadata;
+use std::path::{Path, PathBuf};
+use std::pin::pin;
+use std::process::ExitCode;
+use std::time::{Duration, UNIX_EPOCH};
+
+use tokio::fs;
+
+use chrono::{DateTime, TimeZone, Utc};
+use clap::{Parser, ValueEnum};
+use color_eyre::eyre::{bail, Context, ContextCompat};
+use indicatif::{MultiProgress, ProgressBar};
+use notify::{EventKind, RecursiveMode, Watcher};
+
+use crate::commands::{get, put};
+use crate::config::Config;
+use crate::state::{SyncPairState, SyncState, SyncedFile};
+use crate::utils::terminal::USER_ATTENDED;
+use crate::{utils, Error, Result};
+
+#[derive(Debug, Clone, PartialEq, Parser)]
+#[command(author, rename_all = "kebab-case")]
+pub struct Opts {
+    /// The direction in which changes are propagated
+    #[arg(long, short, value_enum, default_value_t = SyncMode::TwoWay)]
+    mode: SyncMode,
+    /// Only display the actions that would be performed, without performing them
+    #[arg(long, short = 'n')]
+    dry_run: bool,
+    /// Delete files and folders that are missing from the source side (only for `push` and `pull` modes)
+    #[arg(long)]
+    delete: bool,
+    /// Keep running and synchronize changes from either side as they happen
+    #[arg(long, short)]
+    watch: bool,
+    /// How long to wait for changes to settle down before synchronizing them (only for `--watch`)
+    #[arg(
+        long,
+        value_parser(crate::serde_utils::duration::parse_duration),
+        default_value = "2s"
+    )]
+    debounce: Duration,
+    /// Path to the local folder to synchronize
+    local: PathBuf,
+    /// Path (eg. `/Root/folder`) or handle (eg. `H:gZlB3JxS`) to the MEGA folder to synchronize
+    remote: String,
+}
+
+/// The direction in which changes are propagated during a synchronization.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+#[value(rename_all = "kebab-case")]
+pub enum SyncMode {
+    /// Propagate changes in both directions (files modified on both sides are left untouched)
+    TwoWay,
+    /// Propagate local changes to MEGA
+    Push,
+    /// Propagate changes from MEGA to the local folder
+    Pull,
+}
+
+impl Opts {
+    pub fn may_need_user_session(&self) -> bool {
+        true
+    }
+}
+
+/// A local file involved in a synchronization.
+#[derive(Debug, Clone, PartialEq)]
+struct LocalFile {
+    path: PathBuf,
+    size: u64,
+    modified_at: DateTime<Utc>,
+}
+
+/// An action to perform in order to reconcile the local and remote folders.
+#[derive(Debug, Clone, PartialEq)]
+enum SyncAction {
+    /// Create a missing local folder.
+    CreateLocalFolder { relative_path: String },
+    /// Create a missing remote folder.
+    CreateRemoteFolder { relative_path: String },
+    /// Upload a local file, then move the remote file it replaces (if any) to the Rubbish Bin.
+    Upload {
+        relative_path: String,
+        replaced_handle: Option<String>,
+    },
+    /// Download a remote file, overwriting the local file (if any).
+    Download {
+        relative_path: String,
+        handle: String,
+    },
+    /// Delete a local file.
+    DeleteLocal { relative_path: String },
+    /// Move a remote file to the Rubbish Bin.
+    DeleteRemote {
+        relative_path: String,
+        handle: String,
+    },
+    /// Move (or rename) a remote file, following a local move.
+    MoveRemote {
+        from: String,
+        relative_path: String,
+        handle: String,
+    },
+    /// Move (or rename) a local file, following a remote move.
+    MoveLocal { from: String, relative_path: String },
+    /// Delete a local folder, along with its contents.
+    DeleteLocalFolder { relative_path: String },
+    /// Move a remote folder (along with its contents) to the Rubbish Bin.
+    DeleteRemoteFolder { relative_path: String },
+}
+
+impl SyncAction {
+    /// Returns the path affected by the action (the destination, for moves).
+    fn relative_path(&self) -> &str {
+        match self {
+            SyncAction::CreateLocalFolder { relative_path }
+            | SyncAction::CreateRemoteFolder { relative_path }
+            | SyncAction::Upload { relative_path, .. }
+            | SyncAction::Download { relative_path, .. }
+            | SyncAction::DeleteLocal { relative_path }
+            | SyncAction::DeleteRemote { relative_path, .. }
+            | SyncAction::MoveRemote { relative_path, .. }
+            | SyncAction::MoveLocal { relative_path, .. }
+            | SyncAction::DeleteLocalFolder { relative_path }
+            | SyncAction::DeleteRemoteFolder { relative_path } => relative_path,
+        }
+    }
+}
+
+impl fmt::Display for SyncAction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SyncAction::CreateLocalFolder { relative_path } => {
+                write!(f, "create local folder `{relative_path}`")
+            }
+            SyncAction::CreateRemoteFolder { relative_path } => {
+                write!(f, "create remote folder `{relative_path}`")
+            }
+            SyncAction::Upload { relative_path, .. } => write!(f, "upload `{relative_path}`"),
+            SyncAction::Download { relative_path, .. } => write!(f, "download `{relative_path}`"),
+            SyncAction::DeleteLocal { relative_path } => {
+                write!(f, "delete local file `{relative_path}`")
+            }
+            SyncAction::DeleteRemote { relative_path, .. } => {
+                write!(f, "delete remote file `{relative_path}`")
+            }
+            SyncAction::MoveRemote {
+                from,
+                relative_path,
+                ..
+            } => write!(f, "move remote file `{from}` to `{relative_path}`"),
+            SyncAction::MoveLocal {
+                from,
+                relative_path,
+            } => write!(f, "move local file `{from}` to `{relative_path}`"),
+            SyncAction::DeleteLocalFolder { relative_path } => {
+                write!(f, "delete local folder `{relative_path}`")
+            }
+            SyncAction::DeleteRemoteFolder { relative_path } => {
+                write!(f, "delete remote folder `{relative_path}`")
+            }
+        }
+    }
+}
+
+pub async fn handle(_: Config, mega: &mega::Client, opts: Opts) -> Result<ExitCode> {
+    if opts.delete && opts.mode == SyncMode::TwoWay {
+        bail!("`--delete` can only be used with the `push` or `pull` modes");
+    }
+    if opts.watch && opts.dry_run {
+        bail!("`--watch` cannot be used with `--dry-run`");
+    }
+
+    let mut nodes = utils::nodes::fetch_own_nodes(mega).await?;
+
+    let root = if let Some(handle) = opts.remote.strip_prefix("H:") {
+        nodes
+            .get_node_by_handle(handle)
+            .context("could not find node (by handle)")?
+    } else {
+        nodes
+            .get_node_by_path(&opts.remote)
+            .context("could not find node (by path)")?
+    };
+
+    if root.kind().is_file() {
+        bail!("the remote node to synchronize must be a folder");
+    }
+
+    let root_handle = root.handle().to_string();
+
+    if !opts.local.exists() {
+        if opts.dry_run {
+            bail!(
+                "the local folder `{0}` does not exist (it would be created without `--dry-run`)",
+                opts.local.display(),
+            );
+        }
+
+        fs::create_dir_all(&opts.local)
+            .await
+            .context("could not create local folder")?;
+    }
+
+    let local_root = fs::canonicalize(&opts.local)
+        .await
+        .context("could not resolve local folder path")?;
+
+    let result = synchronize(mega, &opts, &mut nodes, &local_root, &root_handle).await;
+
+    if opts.watch {
+        let has_failed = match result {
+            Ok(_) => false,
+            Err(error) => {
+                crate::error!(
+                    to: std::io::stderr(),
+                    from: "mega:sync",
+                    "could not synchronize: {error:#}"
+                )?;
+                true
+            }
+        };
+
+        return watch(
+            mega,
+            &opts,
+            &mut nodes,
+            &local_root,
+            &root_handle,
+            has_failed,
+        )
+        .await;
+    }
+
+    let is_success = result?;
+
+    if is_success {
+        Ok(ExitCode::SUCCESS)
+    } else {
+        Ok(ExitCode::FAILURE)
+    }
+}
+
+/// A change detected while watching the local and remote folders.
+enum WatchedChange {
+    Local(notify::Result<notify::Event>),
+    Remote(mega::Result<mega::EventBatch>),
+}
+
+/// Continuously synchronizes the local and remote folders, as changes happen on either side.
+///
+/// Errors are reported without stopping (retrying with an increasing delay),
+/// so only an interruption (or failing to set up the local file watcher) ends the loop.
+async fn watch(
+    mega: &mega::Client,
+    opts: &Opts,
+    nodes: &mut mega::Nodes,
+    local_root: &Path,
+    root_handle: &str,
+    has_failed: bool,
+) -> Result<ExitCode> {
+    let (sender, receiver) = async_channel::unbounded();
+
+    let mut watcher = notify::recommended_watcher(move |event| {
+        // the receiver outlives the watcher, so this can't fail.
+        let _ = sender.send_blocking(event);
+    })
+    .context("could not create local file watcher")?;
+
+    watcher
+        .watch(local_root, RecursiveMode::Recursive)
+        .context("could not watch local folder")?;
+
+    let ctrl_c = tokio::signal::ctrl_c();
+    let mut ctrl_c = pin!(ctrl_c);
+
+    // the number of consecutive failed attempts, used to back off before trying again.
+    let mut failures: u32 = u32::from(has_failed);
+
+    loop {
+        if failures > 0 {
+            let delay = Duration::from_secs(1 << failures.min(6));
+
+            let maybe_bar = USER_ATTENDED.then(|| {
+                let bar = ProgressBar::new_spinner();
+                bar.set_style(utils::terminal::spinner_style());
+                bar.set_message(format!(
+                    "retrying in {0}s (CTRL-C to terminate)...",
+                    delay.as_secs(),
+                ));
+                bar.enable_steady_tick(Duration::from_millis(75));
+                bar
+            });
+
+            let is_interrupted = tokio::select! {
+                biased;
+                _ = &mut ctrl_c => true,
+                _ = tokio::time::sleep(delay) => false,
+            };
+
+            if let Some(bar) = maybe_bar {
+                bar.finish_and_clear();
+            }
+
+            if is_interrupted {
+                return Ok(ExitCode::SUCCESS);
+            }
+
+            // changes may have been missed in the meantime, so the nodes are fetched again before synchronizing.
+            let result = async {
+                *nodes = utils::nodes::fetch_own_nodes(mega).await?;
+                synchronize(mega, opts, nodes, local_root, root_handle).await
+            }
+            .await;
+
+            match result {
+                Ok(_) => failures = 0,
+                Err(error) => {
+                    failures = failures.saturating_add(1);
+                    crate::error!(
+                        to: std::io::stderr(),
+                        from: "mega:sync",
+                        "could not synchronize: {error:#}"
+                    )?;
+                }
+            }
+            continue;
+        }
+
+        let mut maybe_bar = USER_ATTENDED.then(|| {
+            let bar = ProgressBar::new_spinner();
+            bar.set_style(utils::terminal::spinner_style());
+            bar.set_message("waiting for changes (CTRL-C to terminate)...");
+            bar.enable_steady_tick(Duration::from_millis(75));
+            bar
+        });
+
+        let change = tokio::select! {
+            biased;
+            _ = &mut ctrl_c => {
+                if let Some(bar) = maybe_bar {
+                    bar.finish_and_clear();
+                }
+
+                return Ok(ExitCode::SUCCESS);
+            },
+            event = receiver.recv() => WatchedChange::Local(event?),
+            events = mega.wait_events(nodes) => WatchedChange::Remote(events),
+        };
+
+        // errors are reported without stopping, since they are usually transient (eg. network issues).
+        let result = async {
+            let has_changed = match change {
+                WatchedChange::Local(event) => {
+                    let event = event.context("could not watch local folder")?;
+                    !matches!(event.kind, EventKind::Access(_))
+                }
+                WatchedChange::Remote(events) => {
+                    let events = events.context("could not wait for MEGA events")?;
+                    nodes.apply_events(events)?;
+                    true
+                }
+            };
+
+            if !has_changed {
+                return Ok(());
+            }
+
+            // wait for local changes to settle down, to avoid synchronizing files while they are being written.
+            while let Ok(event) = tokio::time::timeout(opts.debounce, receiver.recv()).await {
+                event?.context("could not watch local folder")?;
+            }
+
+            if let Some(bar) = maybe_bar.take() {
+                bar.finish_and_clear();
+            }
+
+            synchronize(mega, opts, nodes, local_root, root_handle).await?;
+
+            Ok::<_, Error>(())
+        }
+        .await;
+
+        if let Some(bar) = maybe_bar {
+            bar.finish_and_clear();
+        }
+
+        if let Err(error) = result {
+            failures = 1;
+            crate::error!(
+                to: std::io::stderr(),
+                from: "mega:sync",
+                "could not synchronize: {error:#}"
+            )?;
+        }
+    }
+}
+
+/// Performs a single synchronization pass between the local and remote folders,
+/// updating the persisted sync state accordingly.
+///
+/// Returns whether all the actions were performed successfully.
+async fn synchronize(
+    mega: &mega::Client,
+    opts: &Opts,
+    nodes: &mut mega::Nodes,
+    local_root: &Path,
+    root_handle: &str,
+) -> Result<bool> {
+    let root = nodes
+        .get_node_by_handle(root_handle)
+        .context("could not find node (by handle)")?;
+    let root_path = utils::nodes::construct_full_path(nodes, root);
+
+    let (local_folders, local_files) = collect_local_files(local_root).await?;
+    let (remote_folders, remote_files) = collect_remote_files(nodes, root);
+
+    // the sync state is re-loaded on every pass, since other processes may synchronize other pairs.
+    let previous = SyncState::load()
+        .context("could not load sync state")?
+        .pair(local_root, root_handle)
+        .cloned()
+        .unwrap_or_else(|| SyncPairState {
+            local_path: local_root.to_path_buf(),
+            remote_handle: root_handle.to_string(),
+            files: BTreeMap::default(),
+            folders: BTreeSet::default(),
+        });
+
+    let (actions, in_sync) = plan_actions(
+        opts,
+        nodes,
+        &previous,
+        &local_folders,
+        &local_files,
+        &remote_folders,
+        &remote_files,
+    )
+    .await?;
+
+    if actions.is_empty() {
+        if !opts.dry_run {
+            let files = next_synced_files(
+                nodes,
+                &previous.files,
+                &local_files,
+                &remote_files,
+                in_sync,
+                &[],
+            )?;
+            SyncState::store_pair(SyncPairState {
+                local_path: local_root.to_path_buf(),
+                remote_handle: root_handle.to_string(),
+                files,
+                folders: local_folders
+                    .intersection(&remote_folders)
+                    .cloned()
+                    .collect(),
+            })
+            .context("could not store sync state")?;
+        }
+
+        crate::success!(
+            to: std::io::stdout(),
+            "`{0}` and `{1}` are already in sync !",
+            local_root.display(),
+            root_path,
+        )?;
+        return Ok(true);
+    }
+
+    if opts.dry_run {
+        for action in actions.iter() {
+            crate::info!(
+                to: std::io::stdout(),
+                from: "mega:sync",
+                "would {action}"
+            )?;
+        }
+        return Ok(true);
+    }
+
+    let action_count = u64::try_from(actions.len()).context("could not cast `usize` to `u64`")?;
+
+    let maybe_multi = USER_ATTENDED.then(MultiProgress::new);
+    let maybe_overall = maybe_multi.as_ref().map(|multi| {
+        let bar = multi.add(ProgressBar::new(action_count));
+        bar.set_style(utils::terminal::discrete_progress_style());
+        bar.set_message(format!(
+            "synchronizing `{0}` with `{1}`...",
+            local_root.display(),
+            root_path,
+        ));
+        bar
+    });
+
+    // folders are deleted last, once the files they contained had a chance to be moved elsewhere.
+    let (folder_deletions, actions): (Vec<_>, Vec<_>) = actions.into_iter().partition(|action| {
+        matches!(
+            action,
+            SyncAction::DeleteLocalFolder { .. } | SyncAction::DeleteRemoteFolder { .. }
+        )
+    });
+
+    // folders are created first (parents before children), so that files have somewhere to go.
+    let (folder_actions, file_actions): (Vec<_>, Vec<_>) =
+        actions.into_iter().partition(|action| {
+            matches!(
+                action,
+                SyncAction::CreateLocalFolder { .. } | SyncAction::CreateRemoteFolder { .. }
+            )
+        });
+
+    let mut errors: Vec<(SyncAction, Error)> = Vec::default();
+
+    for action in folder_actions {
+        let result = match &action {
+            SyncAction::CreateLocalFolder { relative_path } => {
+                fs::create_dir_all(local_root.join(relative_path))
+                    .await
+                    .context("could not create local folder")
+            }
+            SyncAction::CreateRemoteFolder { relative_path } => {
+                let (parent, name) = split_remote_path(&root_path, relative_path);
+                put::create_remote_folders(mega, nodes, vec![(parent, name.to_string())]).await
+            }
+            _ => unreachable!(),
+        };
+
+        if let Err(error) = result {
+            errors.push((action, error));
+        }
+
+        if let Some(overall) = maybe_overall.as_ref() {
+            overall.inc(1);
+        }
+    }
+
+    let mut performed = Vec::default();
+
+    for action in file_actions.into_iter().chain(folder_deletions) {
+        let maybe_bar = maybe_multi
+            .as_ref()
+            .map(|multi| multi.add(ProgressBar::new(0)));
+
+        let result = perform_action(
+            maybe_bar.as_ref(),
+            mega,
+            nodes,
+            local_root,
+            &root_path,
+            &action,
+        )
+        .await;
+
+        match result {
+            Ok(()) => performed.push(action),
+            Err(error) => errors.push((action, error)),
+        }
+
+        if let (Some(multi), Some(bar)) = (maybe_multi.as_ref(), maybe_bar) {
+            multi.remove(&bar);
+        }
+        if let Some(overall) = maybe_overall.as_ref() {
+            overall.inc(1);
+        }
+    }
+
+    if let Some(multi) = maybe_multi.as_ref() {
+        multi.clear()?;
+    }
+
+    // the nodes and local files are collected again, to record the outcome of the performed actions.
+    *nodes = utils::nodes::fetch_own_nodes(mega).await?;
+    let root = nodes
+        .get_node_by_handle(root_handle)
+        .context("could not find node (by handle)")?;
+    let (local_folders, local_files) = collect_local_files(local_root).await?;
+    let (remote_folders, remote_files) = collect_remote_files(nodes, root);
+
+    let files = next_synced_files(
+        nodes,
+        &previous.files,
+        &local_files,
+        &remote_files,
+        in_sync,
+        &performed,
+    )?;
+
+    SyncState::store_pair(SyncPairState {
+        local_path: local_root.to_path_buf(),
+        remote_handle: root_handle.to_string(),
+        files,
+        folders: local_folders
+            .intersection(&remote_folders)
+            .cloned()
+            .collect(),
+    })
+    .context("could not store sync state")?;
+
+    if !errors.is_empty() {
+        for (action, error) in errors.iter() {
+            crate::error!(
+                to: std::io::stderr(),
+                from: "mega:sync",
+                "could not {action}: {error:#}"
+            )?;
+        }
+
+        crate::error!(
+            to: std::io::stderr(),
+            from: "mega:sync",
+            "{0} out of {1} actions failed",
+            errors.len(),
+            action_count,
+        )?;
+
+        return Ok(false);
+    }
+
+    crate::success!(
+        to: std::io::stdout(),
+        "synchronized `{0}` with `{1}` ({2} actions performed) !",
+        local_root.display(),
+        root_path,
+        action_count,
+    )?;
+
+    Ok(true)
+}
+
+/// Converts a local file's last modification date into a `DateTime<Utc>`.
+fn local_modified_at(metadata: &Metadata) -> Result<DateTime<Utc>> {
+    let date = metadata
+        .modified()
+        .context("could not get last modified date for local file")?
+        .duration_since(UNIX_EPOCH)
+        .context("could not get last modified date for local file")?;
+
+    let seconds = i64::try_from(date.as_secs())
+        .context("could not convert timestamp seconds from `u64` to `i64`")?;
+
+    Utc.timestamp_opt(seconds, date.subsec_nanos())
+        .single()
+        .context("could not convert last modification timestamp to `DateTime<Utc>`")
+}
+
+/// Recursively collects the local folders and files, keyed by their path relative to the root.
+async fn collect_local_files(
+    root: &Path,
+) -> Result<(BTreeSet<String>, BTreeMap<String, LocalFile>)> {
+    let mut folders = BTreeSet::default();
+    let mut files = BTreeMap::default();
+
+    let mut queue = VecDeque::default();
+    queue.push_back((root.to_path_buf(), String::default()));
+
+    while let Some((local_path, relative_path)) = queue.pop_front() {
+        let mut read_dir = fs::read_dir(&local_path)
+            .await
+            .context("could not read local folder")?;
+
+        while let Some(entry) = read_dir.next_entry().await? {
+            let name = entry
+                .file_name()
+                .into_string()
+                .ok()
+                .context("file name is not valid UTF-8")?;
+
+            // leftovers of interrupted downloads are not synchronized.
+            let is_part_file = get::is_part_file_name(&name);
+
+            let relative_path = if relative_path.is_empty() {
+                name
+            } else {
+                format!("{relative_path}/{name}")
+            };
+
+            // TODO: should symbolic links be followed ?
+            let file_type = entry.file_type().await?;
+            if file_type.is_dir() {
+                folders.insert(relative_path.clone());
+                queue.push_back((entry.path(), relative_path));
+            } else if file_type.is_file() && !is_part_file {
+                let metadata = entry.metadata().await?;
+                files.insert(
+                    relative_path,
+                    LocalFile {
+                        path: entry.path(),
+                        size: metadata.len(),
+                        modified_at: local_modified_at(&metadata)?,
+                    },
+                );
+            }
+        }
+    }
+
+    Ok((folders, files))
+}
+
+/// Recursively collects the remote folders and files (as handles), keyed by their path relative to the root.
+fn collect_remote_files(
+    nodes: &mega::Nodes,
+    root: &mega::Node,
+) -> (BTreeSet<String>, BTreeMap<String, String>) {
+    let mut folders = BTreeSet::default();
+    let mut files = BTreeMap::default();
+
+    let mut queue = VecDeque::default();
+    queue.push_back((root, String::default()));
+
+    while let Some((node, relative_path)) = queue.pop_front() {
+        for handle in node.children() {
+            let Some(child) = nodes.get_node_by_handle(handle) else {
+                continue;
+            };
+
+            let relative_path = if relative_path.is_empty() {
+                child.name().to_string()
+            } else {
+                format!("{relative_path}/{0}", child.name())
+            };
+
+            if child.kind().is_file() {
+                files
+                    .entry(relative_path)
+                    .or_insert_with(|| child.handle().to_string());
+            } else {
+                folders.insert(relative_path.clone());
+                queue.push_back((child, relative_path));
+            }
+        }
+    }
+
+    (folders, files)
+}
+
+/// Checks whether a local file still has the same size and modification date as when it was last synchronized.
+fn is_local_unchanged(synced: &SyncedFile, local: &LocalFile) -> bool {
+    synced.size == local.size && local.modified_at.timestamp_nanos_opt() == Some(synced.modified_at)
+}
+
+/// Checks whether a remote file still has the same contents as when it was last synchronized.
+fn is_remote_unchanged(synced: &SyncedFile, node: &mega::Node) -> bool {
+    synced.handle == node.handle()
+        || (synced.size == node.size()
+            && synced.condensed_mac.is_some()
+            && synced.condensed_mac == utils::nodes::condensed_mac_hex(node))
+}
+
+/// Computes the new sync state entries, from the previous ones and the outcome of a synchronization.
+///
+/// The previous entries of files left untouched (skipped conflicts, failed actions, etc...) are kept as-is,
+/// so that they are handled the same way next time.
+fn next_synced_files(
+    nodes: &mega::Nodes,
+    previous: &BTreeMap<String, SyncedFile>,
+    local_files: &BTreeMap<String, LocalFile>,
+    remote_files: &BTreeMap<String, String>,
+    mut in_sync: BTreeSet<String>,
+    performed: &[SyncAction],
+) -> Result<BTreeMap<String, SyncedFile>> {
+    let mut files = previous.clone();
+
+    for action in performed {
+        files.remove(action.relative_path());
+        match action {
+            SyncAction::MoveRemote { from, .. } | SyncAction::MoveLocal { from, .. } => {
+                files.remove(from);
+            }
+            SyncAction::DeleteLocal { .. }
+            | SyncAction::DeleteRemote { .. }
+            | SyncAction::DeleteLocalFolder { .. }
+            | SyncAction::DeleteRemoteFolder { .. } => continue,
+            _ => {}
+        }
+        in_sync.insert(action.relative_path().to_string());
+    }
+
+    // forget about files that are gone from both sides.
+    files.retain(|relative_path, _| {
+        local_files.contains_key(relative_path) || remote_files.contains_key(relative_path)
+    });
+
+    for relative_path in in_sync.iter() {
+        let maybe_local = local_files.get(relative_path);
+        let maybe_remote = remote_files
+            .get(relative_path)
+            .and_then(|handle| nodes.get_node_by_handle(handle));
+
+        let (Some(local), Some(node)) = (maybe_local, maybe_remote) else {
+            continue;
+        };
+
+        let modified_at = local
+            .modified_at
+            .timestamp_nanos_opt()
+            .context("could not convert last modification date to nanoseconds")?;
+
+        files.insert(
+            relative_path.clone(),
+            SyncedFile {
+                handle: node.handle().to_string(),
+                size: local.size,
+                modified_at,
+                condensed_mac: utils::nodes::condensed_mac_hex(node),
+            },
+        );
+    }
+
+    Ok(files)
+}
+
+/// Computes the actions needed to reconcile the local and remote folders,
+/// along with the files that are already identical on both sides.
+async fn plan_actions(
+    opts: &Opts,
+    nodes: &mega::Nodes,
+    previous: &SyncPairState,
+    local_folders: &BTreeSet<String>,
+    local_files: &BTreeMap<String, LocalFile>,
+    remote_folders: &BTreeSet<String>,
+    remote_files: &BTreeMap<String, String>,
+) -> Result<(Vec<SyncAction>, BTreeSet<String>)> {
+    let previous_folders = &previous.folders;
+    let previous = &previous.files;
+
+    let pushes = matches!(opts.mode, SyncMode::TwoWay | SyncMode::Push);
+    let pulls = matches!(opts.mode, SyncMode::TwoWay | SyncMode::Pull);
+
+    let mut actions = Vec::default();
+    let mut in_sync = BTreeSet::default();
+
+    // paths already taken care of by a detected move.
+    let mut moved = BTreeSet::default();
+
+    // a local move is detected when a previously synchronized file disappeared locally
+    // and an untracked local file appeared with the exact same size and modification date.
+    if pushes {
+        for (old_path, synced) in previous.iter() {
+            if local_files.contains_key(old_path)
+                || remote_files.get(old_path) != Some(&synced.handle)
+            {
+                continue;
+            }
+
+            let mut candidates = local_files.iter().filter(|(relative_path, local)| {
+                !previous.contains_key(*relative_path)
+                    && !remote_files.contains_key(*relative_path)
+                    && !moved.contains(*relative_path)
+                    && is_local_unchanged(synced, local)
+            });
+
+            // the move is only followed if there is no ambiguity about the new location.
+            let new_path = match (candidates.next(), candidates.next()) {
+                (Some((new_path, _)), None) => new_path.clone(),
+                _ => continue,
+            };
+
+            moved.insert(old_path.clone());
+            moved.insert(new_path.clone());
+            actions.push(SyncAction::MoveRemote {
+                from: old_path.clone(),
+                relative_path: new_path,
+                handle: synced.handle.clone(),
+            });
+        }
+    }
+
+    // a remote move is detected when a previously synchronized node shows up at another path.
+    if pulls {
+        let remote_paths: BTreeMap<&String, &String> = remote_files
+            .iter()
+            .map(|(relative_path, handle)| (handle, relative_path))
+            .collect();
+
+        for (old_path, synced) in previous.iter() {
+            if moved.contains(old_path) || remote_files.contains_key(old_path) {
+                continue;
+            }
+
+            let Some(local) = local_files.get(old_path) else {
+                continue;
+            };
+            let Some(new_path) = remote_paths.get(&synced.handle) else {
+                continue;
+            };
+
+            if !is_local_unchanged(synced, local)
+                || previous.contains_key(*new_path)
+                || local_files.contains_key(*new_path)
+                || moved.contains(*new_path)
+            {
+                continue;
+            }
+
+            moved.insert(old_path.clone());
+            moved.insert(new_path.to_string());
+            actions.push(SyncAction::MoveLocal {
+                from: old_path.clone(),
+                relative_path: new_path.to_string(),
+            });
+        }
+    }
+
+    // folders missing from one side are either deleted from the other side, or created again.
+    // in two-way mode, a folder is only deleted if it was synchronized before and nothing changed within it since then.
+    let mut deleted_folders: Vec<&String> = Vec::default();
+
+    for relative_path in local_folders.difference(remote_folders) {
+        let is_deleted_remotely = previous_folders.contains(relative_path)
+            && local_folders
+                .iter()
+                .filter(|folder| is_within_folder(folder, relative_path))
+                .all(|folder| previous_folders.contains(folder))
+            && local_files
+                .iter()
+                .filter(|(file, _)| is_within_folder(file, relative_path))
+                .all(|(file, local)| {
+                    moved.contains(file)
+                        || previous
+                            .get(file)
+                            .is_some_and(|synced| is_local_unchanged(synced, local))
+                });
+
+        if (opts.mode == SyncMode::TwoWay && is_deleted_remotely)
+            || (opts.mode == SyncMode::Pull && opts.delete)
+        {
+            // only the topmost deleted folder needs to be deleted.
+            if !deleted_folders
+                .iter()
+                .any(|folder| is_within_folder(relative_path, folder))
+            {
+                actions.push(SyncAction::DeleteLocalFolder {
+                    relative_path: relative_path.clone(),
+                });
+            }
+            deleted_folders.push(relative_path);
+        } else if pushes {
+            actions.push(SyncAction::CreateRemoteFolder {
+                relative_path: relative_path.clone(),
+            });
+        }
+    }
+
+    for relative_path in remote_folders.difference(local_folders) {
+        let is_deleted_locally = previous_folders.contains(relative_path)
+            && remote_folders
+                .iter()
+                .filter(|folder| is_within_folder(folder, relative_path))
+                .all(|folder| previous_folders.contains(folder))
+            && remote_files
+                .iter()
+                .filter(|(file, _)| is_within_folder(file, relative_path))
+                .all(|(file, handle)| {
+                    moved.contains(file)
+                        || previous.get(file).is_some_and(|synced| {
+                            nodes
+                                .get_node_by_handle(handle)
+                                .is_some_and(|node| is_remote_unchanged(synced, node))
+                        })
+                });
+
+        if (opts.mode == SyncMode::TwoWay && is_deleted_locally)
+            || (opts.mode == SyncMode::Push && opts.delete)
+        {
+            if !deleted_folders
+                .iter()
+                .any(|folder| is_within_folder(relative_path, folder))
+            {
+                actions.push(SyncAction::DeleteRemoteFolder {
+                    relative_path: relative_path.clone(),
+                });
+            }
+            deleted_folders.push(relative_path);
+        } else if pulls {
+            actions.push(SyncAction::CreateLocalFolder {
+                relative_path: relative_path.clone(),
+            });
+        }
+    }
+
+    let maybe_bar = USER_ATTENDED.then(|| ProgressBar::new(0));
+
+    // files within deleted folders are taken care of by the deletion of these folders.
+    let relative_paths: BTreeSet<&String> = local_files
+        .keys()
+        .chain(remote_files.keys())
+        .filter(|relative_path| !moved.contains(*relative_path))
+        .filter(|relative_path| {
+            !deleted_folders
+                .iter()
+                .any(|folder| is_within_folder(relative_path, folder))
+        })
+        .collect();
+
+    for relative_path in relative_paths {
+        let maybe_synced = previous.get(relative_path);
+        let maybe_local = local_files.get(relative_path);
+        let maybe_remote = remote_files
+            .get(relative_path)
+            .and_then(|handle| nodes.get_node_by_handle(handle));
+
+        let action = match (maybe_local, maybe_remote) {
+            (Some(local), None) => {
+                // the file was synchronized before, and got deleted remotely since then.
+                let is_deleted_remotely =
+                    maybe_synced.is_some_and(|synced| is_local_unchanged(synced, local));
+
+                if (opts.mode == SyncMode::TwoWay && is_deleted_remotely)
+                    || (opts.mode == SyncMode::Pull && opts.delete)
+                {
+                    Some(SyncAction::DeleteLocal {
+                        relative_path: relative_path.clone(),
+                    })
+                } else if pushes {
+                    Some(SyncAction::Upload {
+                        relative_path: relative_path.clone(),
+                        replaced_handle: None,
+                    })
+                } else {
+                    None
+                }
+            }
+            (None, Some(node)) => {
+                // the file was synchronized before, and got deleted locally since then.
+                let is_deleted_locally =
+                    maybe_synced.is_some_and(|synced| is_remote_unchanged(synced, node));
+
+                if (opts.mode == SyncMode::TwoWay && is_deleted_locally)
+                    || (opts.mode == SyncMode::Push && opts.delete)
+                {
+                    Some(SyncAction::DeleteRemote {
+                        relative_path: relative_path.clone(),
+                        handle: node.handle().to_string(),
+                    })
+                } else if pulls {
+                    Some(SyncAction::Download {
+                        relative_path: relative_path.clone(),
+                        handle: node.handle().to_string(),
+                    })
+                } else {
+                    None
+                }
+            }
+            (Some(local), Some(node)) => {
+                let is_local_unchanged =
+                    maybe_synced.is_some_and(|synced| is_local_unchanged(synced, local));
+                let is_remote_unchanged =
+                    maybe_synced.is_some_and(|synced| is_remote_unchanged(synced, node));
+
+                // files unchanged on both sides since the last synchronization don't need to be hashed again.
+                let is_identical = (is_local_unchanged && is_remote_unchanged)
+                    || (local.size == node.size()
+                        && utils::nodes::is_file_identical(maybe_bar.as_ref(), node, &local.path)
+                            .await?);
+
+                if is_identical {
+                    in_sync.insert(relative_path.clone());
+                    None
+                } else {
+                    let upload = SyncAction::Upload {
+                        relative_path: relative_path.clone(),
+                        replaced_handle: Some(node.handle().to_string()),
+                    };
+                    let download = SyncAction::Download {
+                        relative_path: relative_path.clone(),
+                        handle: node.handle().to_string(),
+                    };
+
+                    match opts.mode {
+                        SyncMode::Push => Some(upload),
+                        SyncMode::Pull => Some(download),
+                        SyncMode::TwoWay if is_local_unchanged => Some(download),
+                        SyncMode::TwoWay if is_remote_unchanged => Some(upload),
+                        SyncMode::TwoWay if maybe_synced.is_some() => {
+                            crate::warning!(
+                                to: std::io::stderr(),
+                                from: "mega:sync",
+                                "`{relative_path}` was modified on both sides since the last synchronization, skipping"
+                            )?;
+                            None
+                        }
+                        SyncMode::TwoWay => {
+                            let local_timestamp = local.modified_at.timestamp();
+                            let remote_timestamp = node
+                                .modified_at()
+                                .or(node.created_at())
+                                .map_or(i64::MIN, |date| date.timestamp());
+
+                            if local_timestamp > remote_timestamp {
+                                Some(upload)
+                            } else if local_timestamp < remote_timestamp {
+                                Some(download)
+                            } else {
+                                crate::warning!(
+                                    to: std::io::stderr(),
+                                    from: "mega:sync",
+                                    "`{relative_path}` differs on both sides with the same modification date, skipping"
+                                )?;
+                                None
+                            }
+                        }
+                    }
+                }
+            }
+            (None, None) => None,
+        };
+
+        actions.extend(action);
+    }
+
+    if let Some(bar) = maybe_bar {
+        bar.finish_and_clear();
+    }
+
+    Ok((actions, in_sync))
+}
+
+/// Checks whether a relative path is located (possibly deeply) within a relative folder path.
+fn is_within_folder(relative_path: &str, folder: &str) -> bool {
+    relative_path
+        .strip_prefix(folder)
+        .is_some_and(|rest| rest.starts_with('/'))
+}
+
+/// Splits a path relative to the remote root into its full parent path and its file name.
+fn split_remote_path<'a>(root_path: &str, relative_path: &'a str) -> (String, &'a str) {
+    match relative_path.rsplit_once('/') {
+        Some((parent, name)) => (format!("{root_path}/{parent}"), name),
+        None => (root_path.to_string(), relative_path),
+    }
+}
+
+/// Performs a single file synchronization action.
+async fn perform_action(
+    maybe_bar: Option<&ProgressBar>,
+    mega: &mega::Client,
+    nodes: &mega::Nodes,
+    local_root: &Path,
+    root_path: &str,
+    action: &SyncAction,
+) -> Result<()> {
+    match action {
+        SyncAction::Upload {
+            relative_path,
+            replaced_handle,
+        } => {
+            let (parent_path, file_name) = split_remote_path(root_path, relative_path);
+
+            let parent_node = nodes
+                .get_node_by_path(&parent_path)
+                .context("could not find parent folder")?;
+
+            if let Some(bar) = maybe_bar {
+                bar.set_style(utils::terminal::standard_progress_style());
+                bar.set_message(format!("uploading `{relative_path}`..."));
+            }
+
+            put::perform_file_upload(
+                maybe_bar,
+                mega,
+                parent_node,
+                file_name,
+                &local_root.join(relative_path),
+            )
+            .await?;
+
+            // the replaced node is only moved to the Rubbish Bin once the upload succeeded.
+            if let Some(handle) = replaced_handle {
+                put::trash_replaced_node(mega, nodes, handle).await?;
+            }
+        }
+        SyncAction::Download {
+            relative_path,
+            handle,
+        } => {
+            let node = nodes
+                .get_node_by_handle(handle)
+                .context("could not find node (by handle)")?;
+
+            if let Some(bar) = maybe_bar {
+                bar.set_style(utils::terminal::standard_progress_style());
+                bar.set_message(format!("downloading `{relative_path}`..."));
+                bar.set_position(0);
+                bar.set_length(node.size());
+                bar.reset();
+            }
+
+            get::perform_file_download(maybe_bar, mega, node, &local_root.join(relative_path))
+                .await?;
+        }
+        SyncAction::DeleteLocal { relative_path } => {
+            fs::remove_file(local_root.join(relative_path))
+                .await
+                .context("could not delete local file")?;
+        }
+        SyncAction::DeleteRemote { handle, .. } => {
+            let node = nodes
+                .get_node_by_handle(handle)
+                .context("could not find node (by handle)")?;
+            let rubbish_bin = nodes.rubbish_bin().context("could not find Rubbish Bin")?;
+            mega.move_node(node, rubbish_bin)
+                .await
+                .context("could not move node to the Rubbish Bin")?;
+        }
+        SyncAction::MoveRemote {
+            relative_path,
+            handle,
+            ..
+        } => {
+            let node = nodes
+                .get_node_by_handle(handle)
+                .context("could not find node (by handle)")?;
+
+            let (parent_path, file_name) = split_remote_path(root_path, relative_path);
+            let parent_node = nodes
+                .get_node_by_path(&parent_path)
+                .context("could not find parent folder")?;
+
+            if node.parent() != Some(parent_node.handle()) {
+                mega.move_node(node, parent_node)
+                    .await
+                    .context("could not move node")?;
+            }
+            if node.name() != file_name {
+                mega.rename_node(node, file_name)
+                    .await
+                    .context("could not rename node")?;
+            }
+        }
+        SyncAction::MoveLocal {
+            from,
+            relative_path,
+        } => {
+            let destination = local_root.join(relative_path);
+            if let Some(parent) = destination.parent() {
+                fs::create_dir_all(parent)
+                    .await
+                    .context("could not create local folder")?;
+            }
+
+            fs::rename(local_root.join(from), destination)
+                .await
+                .context("could not move local file")?;
+        }
+        SyncAction::DeleteLocalFolder { relative_path } => {
+            fs::remove_dir_all(local_root.join(relative_path))
+                .await
+                .context("could not delete local folder")?;
+        }
+        SyncAction::DeleteRemoteFolder { relative_path } => {
+            let node = nodes
+                .get_node_by_path(&format!("{root_path}/{relative_path}"))
+                .context("could not find node (by path)")?;
+            let rubbish_bin = nodes.rubbish_bin().context("could not find Rubbish Bin")?;
+            mega.move_node(node, rubbish_bin)
+                .await
+                .context("could not move node to the Rubbish Bin")?;
+        }
+        SyncAction::CreateLocalFolder { .. } | SyncAction::CreateRemoteFolder { .. } => {}
+    }
+
+    Ok(())
+}
@@ -0,0 +1,104 @@
+use std::process::ExitCode;
+use std::time::Duration;
+
+use chrono::Utc;
+use clap::Parser;
+use color_eyre::eyre::{bail, Context, ContextCompat};
+use dialoguer::theme::ColorfulTheme;
+use dialoguer::Confirm;
+
+use crate::config::Config;
+use crate::utils;
+use crate::utils::terminal::USER_ATTENDED;
+use crate::Result;
+
+#[derive(Debug, Clone, PartialEq, Parser)]
+#[command(author, rename_all = "kebab-case")]
+pub struct Opts {
+    /// Only delete nodes last modified more than the given duration ago (eg. `30d`)
+    /// (the date at which nodes were trashed is not exposed by the `mega` crate)
+    #[arg(long, value_parser(crate::serde_utils::duration::parse_duration))]
+    modified_before: Option<Duration>,
+    /// Do not ask for confirmation before permanently deleting nodes
+    #[arg(long, short = 'y')]
+    yes: bool,
+}
+
+impl Opts {
+    pub fn may_need_user_session(&self) -> bool {
+        true
+    }
+}
+
+pub async fn handle(_: Config, mega: &mega::Client, opts: Opts) -> Result<ExitCode> {
+    let nodes = utils::nodes::fetch_own_nodes(mega).await?;
+
+    let rubbish_bin = nodes.rubbish_bin().context("could not find Rubbish Bin")?;
+
+    let threshold = opts
+        .modified_before
+        .map(|duration| chrono::Duration::from_std(duration).map(|duration| Utc::now() - duration))
+        .transpose()
+        .context("duration is out of range")?;
+
+    let mut trashed_nodes: Vec<_> = rubbish_bin
+        .children()
+        .iter()
+        .filter_map(|handle| nodes.get_node_by_handle(handle))
+        .filter(|node| {
+            threshold.is_none_or(|threshold| {
+                node.modified_at()
+                    .or(node.created_at())
+                    .is_some_and(|date| date < threshold)
+            })
+        })
+        .collect();
+    trashed_nodes.sort_unstable_by_key(|node| node.name());
+
+    if trashed_nodes.is_empty() {
+        crate::info!(to: std::io::stdout(), "nothing to delete from the Rubbish Bin")?;
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    if !opts.yes {
+        if !*USER_ATTENDED {
+            bail!("refusing to permanently delete nodes without confirmation (use `--yes`)");
+        }
+
+        let confirmed = Confirm::with_theme(&ColorfulTheme::default())
+            .with_prompt(format!(
+                "Permanently delete {0} nodes from the Rubbish Bin ?",
+                trashed_nodes.len(),
+            ))
+            .default(false)
+            .interact()?;
+
+        if !confirmed {
+            return Ok(ExitCode::FAILURE);
+        }
+    }
+
+    let mut has_failed = false;
+    for node in trashed_nodes {
+        match mega.delete_node(node).await {
+            Ok(()) => {
+                crate::success!(to: std::io::stdout(), "deleted `{0}` !", node.name())?;
+            }
+            Err(error) => {
+                has_failed = true;
+                crate::error!(
+                    to: std::io::stderr(),
+                    from: "mega:trash",
+                    "could not delete `{0}`: {error}",
+                    node.name(),
+                )?;
+            }
+        }
+    }
+
+    if has_failed {
+        Ok(ExitCode::FAILURE)
+    } else {
+        Ok(ExitCode::SUCCESS)
+    }
+}
@@ -0,0 +1,66 @@
+use std::process::ExitCode;
+
+use clap::Parser;
+use color_eyre::eyre::ContextCompat;
+use indicatif::DecimalBytes;
+
+use crate::config::Config;
+use crate::utils;
+use crate::utils::nodes::NodeInfo;
+use crate::utils::output::OutputFormat;
+use crate::Result;
+
+#[derive(Debug, Clone, PartialEq, Parser)]
+#[command(author, rename_all = "kebab-case")]
+pub struct Opts {
+    /// Display node handles (eg. `H:gZlB3JxS`) alongside their names
+    #[arg(long, short = 'H')]
+    show_handles: bool,
+    /// The format in which to display the nodes
+    #[arg(long, short, value_enum, default_value_t = OutputFormat::Text)]
+    output: OutputFormat,
+}
+
+impl Opts {
+    pub fn may_need_user_session(&self) -> bool {
+        true
+    }
+}
+
+pub async fn handle(_: Config, mega: &mega::Client, opts: Opts) -> Result<ExitCode> {
+    let nodes = utils::nodes::fetch_own_nodes(mega).await?;
+
+    let rubbish_bin = nodes.rubbish_bin().context("could not find Rubbish Bin")?;
+
+    // the original location of trashed nodes is not exposed by the `mega` crate,
+    // so only the nodes themselves are listed.
+    let mut trashed_nodes: Vec<_> = rubbish_bin
+        .children()
+        .iter()
+        .filter_map(|handle| nodes.get_node_by_handle(handle))
+        .collect();
+    trashed_nodes.sort_unstable_by_key(|node| (node.kind().is_file(), node.name()));
+
+    if opts.output.is_json() {
+        let infos: Vec<_> = trashed_nodes
+            .into_iter()
+            .map(|node| NodeInfo::new(&nodes, node))
+            .collect();
+
+        utils::output::print_json_items(opts.output, &infos)?;
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    for node in trashed_nodes {
+        let size = DecimalBytes(utils::nodes::total_size(&nodes, node));
+        let suffix = if node.kind().is_file() { "" } else { "/" };
+
+        if opts.show_handles {
+            println!("- (H:{0}) {1}{suffix} ({size})", node.handle(), node.name());
+        } else {
+            println!("- {0}{suffix} ({size})", node.name());
+        }
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
@@ -0,0 +1,73 @@
+use std::process::ExitCode;
+
+use clap::Subcommand;
+use color_eyre::eyre::{bail, ContextCompat};
+
+pub mod empty;
+pub mod list;
+pub mod restore;
+
+use crate::config::Config;
+use crate::utils;
+use crate::Result;
+
+#[derive(Debug, Clone, PartialEq, Subcommand)]
+#[command(author, rename_all = "kebab-case")]
+pub enum Command {
+    /// List the contents of the Rubbish Bin
+    List(list::Opts),
+    /// Move nodes out of the Rubbish Bin
+    Restore(restore::Opts),
+    /// Permanently delete the contents of the Rubbish Bin
+    Empty(empty::Opts),
+}
+
+impl Command {
+    pub fn may_need_user_session(&self) -> bool {
+        match self {
+            Command::List(opts) => opts.may_need_user_session(),
+            Command::Restore(opts) => opts.may_need_user_session(),
+            Command::Empty(opts) => opts.may_need_user_session(),
+        }
+    }
+}
+
+pub async fn handle(config: Config, mega: &mega::Client, opts: Command) -> Result<ExitCode> {
+    match opts {
+        Command::List(opts) => list::handle(config, mega, opts).await,
+        Command::Restore(opts) => restore::handle(config, mega, opts).await,
+        Command::Empty(opts) => empty::handle(config, mega, opts).await,
+    }
+}
+
+/// Finds a node within the Rubbish Bin, either by handle (eg. `H:gZlB3JxS`)
+/// or by path relative to the Rubbish Bin (eg. `folder/file.txt`).
+fn find_trashed_node<'a>(nodes: &'a mega::Nodes, path: &str) -> Result<&'a mega::Node> {
+    let rubbish_bin = nodes.rubbish_bin().context("could not find Rubbish Bin")?;
+
+    let node = if let Some(handle) = path.strip_prefix("H:") {
+        nodes
+            .get_node_by_handle(handle)
+            .context("could not find node (by handle)")?
+    } else {
+        let rubbish_bin_path = utils::nodes::construct_full_path(nodes, rubbish_bin);
+        let path = path.trim_matches('/');
+        nodes
+            .get_node_by_path(&format!("{rubbish_bin_path}/{path}"))
+            .context("could not find node (by path) within the Rubbish Bin")?
+    };
+
+    let mut maybe_ancestor = node
+        .parent()
+        .and_then(|handle| nodes.get_node_by_handle(handle));
+    while let Some(ancestor) = maybe_ancestor {
+        if ancestor.handle() == rubbish_bin.handle() {
+            return Ok(node);
+        }
+        maybe_ancestor = ancestor
+            .parent()
+            .and_then(|handle| nodes.get_node_by_handle(handle));
+    }
+
+    bail!("`{path}` is not within the Rubbish Bin");
+}
@@ -0,0 +1,88 @@
+use std::process::ExitCode;
+use std::time::Duration;
+
+use clap::Parser;
+use color_eyre::eyre::{bail, Context, ContextCompat};
+use indicatif::ProgressBar;
+
+use crate::commands::put;
+use crate::config::Config;
+use crate::utils;
+use crate::utils::terminal::USER_ATTENDED;
+use crate::Result;
+
+#[derive(Debug, Clone, PartialEq, Parser)]
+#[command(author, rename_all = "kebab-case")]
+pub struct Opts {
+    /// Path relative to the Rubbish Bin (eg. `file.txt`) or handle (eg. `H:gZlB3JxS`) to the MEGA node to restore
+    path: String,
+    /// Path (eg. `/Root/folder`) or handle (eg. `H:gZlB3JxS`) to the MEGA folder to restore into
+    /// (the Cloud Drive, if omitted, since original locations are not exposed by the `mega` crate)
+    destination: Option<String>,
+}
+
+impl Opts {
+    pub fn may_need_user_session(&self) -> bool {
+        true
+    }
+}
+
+pub async fn handle(_: Config, mega: &mega::Client, opts: Opts) -> Result<ExitCode> {
+    let nodes = utils::nodes::fetch_own_nodes(mega).await?;
+
+    let node = super::find_trashed_node(&nodes, &opts.path)?;
+
+    let destination = match opts.destination.as_deref() {
+        Some(path) => {
+            if let Some(handle) = path.strip_prefix("H:") {
+                nodes
+                    .get_node_by_handle(handle)
+                    .context("could not find destination folder (by handle)")?
+            } else {
+                nodes
+                    .get_node_by_path(path.trim_end_matches('/'))
+                    .context("could not find destination folder (by path)")?
+            }
+        }
+        None => nodes
+            .cloud_drive()
+            .context("could not find the Cloud Drive")?,
+    };
+
+    if destination.kind().is_file() {
+        bail!("the destination must be a folder");
+    }
+
+    if put::find_child(&nodes, destination, node.name()).is_some() {
+        bail!(
+            "a node named `{0}` already exists in the destination folder",
+            node.name(),
+        );
+    }
+
+    let destination_path = utils::nodes::construct_full_path(&nodes, destination);
+
+    let maybe_bar = USER_ATTENDED.then(|| {
+        let bar = ProgressBar::new_spinner();
+        bar.set_style(utils::terminal::spinner_style());
+        bar.set_message(format!(
+            "restoring `{0}` into `{destination_path}`...",
+            node.name(),
+        ));
+        bar.enable_steady_tick(Duration::from_millis(75));
+        bar
+    });
+
+    mega.move_node(node, destination)
+        .await
+        .context("could not move node out of the Rubbish Bin")?;
+
+    if let Some(bar) = maybe_bar {
+        bar.finish_with_message(format!(
+            "restored `{0}` into `{destination_path}` !",
+            node.name(),
+        ));
+    }
+
+    Ok(ExitCode::SUCCESS)
+}
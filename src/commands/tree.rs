@@ -71,9 +71,9 @@ pub async fn handle(_: Config, mega: &mega::Client, opts: Opts) -> Result<ExitCo
     let formatting = TreeFormatting::dir_tree(FormatCharacters::box_chars());
 
     if let Some(path) = opts.path {
-        let node = if path.starts_with("H:") {
+        let node = if let Some(handle) = path.strip_prefix("H:") {
             nodes
-                .get_node_by_handle(&path[2..])
+                .get_node_by_handle(handle)
                 .context("could not find node (by handle)")?
         } else {
             nodes
@@ -127,7 +127,7 @@ fn format_node_label(node: &mega::Node, show_handles: bool) -> String {
         if show_handles {
             format!("(H:{0}) {1}", node.handle(), node.name())
         } else {
-            format!("{0}", node.name())
+            node.name().to_string()
         }
     } else {
         if show_handles {
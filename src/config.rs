@@ -18,7 +18,7 @@ pub enum Config {
 }
 
 /// The V1 configuration structure.
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct V1Config {
     /// Authentication configuration.
     pub auth: AuthConfig,
@@ -27,10 +27,17 @@ pub struct V1Config {
 }
 
 /// Authentication configuration.
-#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct AuthConfig {
     /// The serialized session string.
     pub session: Option<String>,
+    /// The base32-encoded MFA shared secret (as shown during 2FA enrollment),
+    /// used to generate MFA codes automatically when logging in.
+    ///
+    /// This is opt-in, and storing this secret on the same machine as the credentials
+    /// significantly weakens two-factor authentication: anyone able to read this file
+    /// can generate valid MFA codes. Only use this for unattended service accounts.
+    pub mfa_secret: Option<String>,
 }
 
 /// Configuration for the MEGA API client.
@@ -65,21 +72,6 @@ impl Default for Config {
     }
 }
 
-impl Default for V1Config {
-    fn default() -> Self {
-        Self {
-            auth: AuthConfig::default(),
-            client: ClientConfig::default(),
-        }
-    }
-}
-
-impl Default for AuthConfig {
-    fn default() -> Self {
-        Self { session: None }
-    }
-}
-
 impl Default for ClientConfig {
     fn default() -> Self {
         Self {
@@ -12,6 +12,7 @@ pub mod commands;
 pub mod config;
 pub mod format;
 pub mod serde_utils;
+pub mod state;
 pub mod utils;
 
 use crate::config::{Config, CONFIG_NAME};
@@ -6,7 +6,7 @@ use serde::de::{self, Deserializer, Visitor};
 /// Deserializes either a number or a string representing a human-readable duration into a [`Duration`].
 ///
 /// The string format supported is roughly (expressed as a regular expression):
-/// `^\s*(?P<number>\d+)\s*(?P<unit>ns|µs|ms|s|min|h)\s*$`
+/// `^\s*(?P<number>\d+)\s*(?P<unit>ns|µs|ms|s|min|h|d)\s*$`
 pub fn deserialize<'de, D>(deserializer: D) -> Result<Duration, D::Error>
 where
     D: Deserializer<'de>,
@@ -89,7 +89,7 @@ where
         {
             let Ok(value) = u64::try_from(value) else {
                 return Err(de::Error::invalid_value(
-                    de::Unexpected::Signed(i64::from(value)),
+                    de::Unexpected::Signed(value),
                     &self,
                 ));
             };
@@ -183,7 +183,7 @@ where
         {
             let Ok(value) = u64::try_from(value) else {
                 return Err(de::Error::invalid_value(
-                    de::Unexpected::Signed(i64::from(value)),
+                    de::Unexpected::Signed(value),
                     &self,
                 ));
             };
@@ -237,10 +237,20 @@ where
         "s" => Duration::from_secs(number),
         "min" => Duration::from_secs(number * 60),
         "h" => Duration::from_secs(number * 3600), // 60 * 60 == 3600
+        "d" => {
+            // 24 * 60 * 60 == 86400
+            let Some(seconds) = number.checked_mul(86400) else {
+                return Err(de::Error::invalid_value(
+                    de::Unexpected::Unsigned(number),
+                    &"a number of days representable as a `u64` of seconds",
+                ));
+            };
+            Duration::from_secs(seconds)
+        }
         unit_str => {
             return Err(de::Error::invalid_value(
                 de::Unexpected::Str(unit_str),
-                &"a valid time unit (`ns`, `µs`, `ms`, `s`, `min`, or `h`)",
+                &"a valid time unit (`ns`, `µs`, `ms`, `s`, `min`, `h`, or `d`)",
             ));
         }
     };
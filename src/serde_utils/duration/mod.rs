@@ -37,6 +37,11 @@ pub fn parse_duration(value: &str) -> Result<Duration, ParseDurationError> {
         "s" => Duration::from_secs(number),
         "min" => Duration::from_secs(number * 60),
         "h" => Duration::from_secs(number * 3600), // 60 * 60 == 3600
+        "d" => {
+            // 24 * 60 * 60 == 86400
+            let seconds = number.checked_mul(86400).ok_or(ParseDurationError)?;
+            Duration::from_secs(seconds)
+        }
         _ => {
             return Err(ParseDurationError);
         }
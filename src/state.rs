@@ -0,0 +1,98 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use crate::config::CONFIG_NAME;
+use crate::Result;
+
+pub const SYNC_STATE_FILE_NAME: &str = "sync-state.toml";
+
+/// The persisted state of previous synchronizations.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SyncState {
+    /// The state of each synchronized pair of folders.
+    #[serde(default)]
+    pub pairs: Vec<SyncPairState>,
+}
+
+/// The persisted state of a synchronized pair of folders.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SyncPairState {
+    /// The canonical path of the local folder.
+    pub local_path: PathBuf,
+    /// The handle of the remote MEGA folder.
+    pub remote_handle: String,
+    /// The synchronized files, keyed by their path relative to both folders.
+    #[serde(default)]
+    pub files: BTreeMap<String, SyncedFile>,
+    /// The synchronized folders, as paths relative to both folders.
+    #[serde(default)]
+    pub folders: BTreeSet<String>,
+}
+
+/// The state of a file, as of its last synchronization.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SyncedFile {
+    /// The handle of the remote node.
+    pub handle: String,
+    /// The size (in bytes) of the file.
+    pub size: u64,
+    /// The last modification date of the local file (as nanoseconds since the Unix epoch).
+    pub modified_at: i64,
+    /// The condensed MAC of the remote node (as hexadecimal).
+    pub condensed_mac: Option<String>,
+}
+
+impl SyncState {
+    /// Returns the path to the sync state file (stored next to the configuration file).
+    pub fn path() -> Result<PathBuf> {
+        let config_path = confy::get_configuration_file_path(CONFIG_NAME, None)?;
+        Ok(config_path.with_file_name(SYNC_STATE_FILE_NAME))
+    }
+
+    /// Loads the sync state from disk (or an empty one, if missing).
+    ///
+    /// The file is not created when missing (unlike `confy::load_path` would do),
+    /// so that `--dry-run` synchronizations leave no trace behind.
+    pub fn load() -> Result<Self> {
+        let path = Self::path()?;
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let state = confy::load_path(path)?;
+        Ok(state)
+    }
+
+    /// Persists the sync state to disk.
+    pub fn store(&self) -> Result<()> {
+        confy::store_path(Self::path()?, self)?;
+        Ok(())
+    }
+
+    /// Gets the state of a synchronized pair of folders, if it has been synchronized before.
+    pub fn pair(&self, local_path: &Path, remote_handle: &str) -> Option<&SyncPairState> {
+        self.pairs
+            .iter()
+            .find(|pair| pair.local_path == local_path && pair.remote_handle == remote_handle)
+    }
+
+    /// Replaces (or inserts) the state of a synchronized pair of folders.
+    pub fn set_pair(&mut self, pair: SyncPairState) {
+        self.pairs.retain(|it| {
+            it.local_path != pair.local_path || it.remote_handle != pair.remote_handle
+        });
+        self.pairs.push(pair);
+    }
+
+    /// Persists the state of a single synchronized pair of folders.
+    ///
+    /// The sync state is re-loaded right before being stored, so that the pairs
+    /// concurrently synchronized by other processes are kept as they are.
+    pub fn store_pair(pair: SyncPairState) -> Result<()> {
+        let mut state = Self::load()?;
+        state.set_pair(pair);
+        state.store()
+    }
+}
@@ -1,5 +1,7 @@
 pub mod nodes;
+pub mod output;
 pub mod terminal;
+pub mod totp;
 
 /// Returns whether the argument is a public MEGA URL rather than a path or a node handle.
 pub fn is_public_link(input: &str) -> bool {
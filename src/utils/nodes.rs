@@ -1,3 +1,67 @@
+use std::path::Path;
+use std::time::Duration;
+
+use tokio::fs::File;
+
+use async_read_progress::AsyncReadProgressExt;
+use color_eyre::eyre::{Context, ContextCompat};
+use futures::future::Either;
+use indicatif::ProgressBar;
+use serde::Serialize;
+use tokio_util::compat::TokioAsyncReadCompatExt;
+
+use crate::utils;
+use crate::Result;
+
+/// The machine-readable description of a node.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct NodeInfo {
+    /// The handle of the node.
+    pub handle: String,
+    /// The name of the node.
+    pub name: String,
+    /// The full path to the node.
+    pub path: String,
+    /// The kind of the node.
+    pub kind: &'static str,
+    /// The size (in bytes) of the node.
+    pub size: u64,
+    /// The creation date of the node (in RFC 3339 format).
+    pub created_at: Option<String>,
+    /// The last modification date of the node (in RFC 3339 format).
+    pub modified_at: Option<String>,
+    /// The handle of the node's parent, if any.
+    pub parent: Option<String>,
+}
+
+impl NodeInfo {
+    /// Describes a node.
+    pub fn new(nodes: &mega::Nodes, node: &mega::Node) -> Self {
+        Self {
+            handle: node.handle().to_string(),
+            name: node.name().to_string(),
+            path: construct_full_path(nodes, node),
+            kind: kind_name(node.kind()),
+            size: node.size(),
+            created_at: node.created_at().map(|date| date.to_rfc3339()),
+            modified_at: node.modified_at().map(|date| date.to_rfc3339()),
+            parent: node.parent().map(str::to_string),
+        }
+    }
+}
+
+/// Returns a short name for a kind of node.
+pub fn kind_name(kind: mega::NodeKind) -> &'static str {
+    match kind {
+        mega::NodeKind::File => "file",
+        mega::NodeKind::Folder => "folder",
+        mega::NodeKind::Root => "root",
+        mega::NodeKind::Inbox => "inbox",
+        mega::NodeKind::Trash => "rubbish-bin",
+        mega::NodeKind::Unknown => "unknown",
+    }
+}
+
 /// Constructs the full path to a node, by visiting all of its parents.
 pub fn construct_full_path(nodes: &mega::Nodes, node: &mega::Node) -> String {
     let mut full_path = node
@@ -12,6 +76,25 @@ pub fn construct_full_path(nodes: &mega::Nodes, node: &mega::Node) -> String {
     full_path
 }
 
+/// Formats the condensed MAC of a node as hexadecimal.
+pub fn condensed_mac_hex(node: &mega::Node) -> Option<String> {
+    node.condensed_mac()
+        .map(|mac| mac.iter().map(|byte| format!("{byte:02x}")).collect())
+}
+
+/// Computes the total size of the files within a node (recursively).
+pub fn total_size(nodes: &mega::Nodes, node: &mega::Node) -> u64 {
+    if node.kind().is_file() {
+        return node.size();
+    }
+
+    node.children()
+        .iter()
+        .filter_map(|handle| nodes.get_node_by_handle(handle))
+        .map(|child| total_size(nodes, child))
+        .sum()
+}
+
 /// Constructs the relative path to a node from a reference (root) node.
 pub fn construct_relative_path(
     nodes: &mega::Nodes,
@@ -36,3 +119,111 @@ pub fn construct_relative_path(
         node.name().to_string()
     }
 }
+
+/// Fetches the nodes of the account, with a spinner.
+pub async fn fetch_own_nodes(mega: &mega::Client) -> Result<mega::Nodes> {
+    let maybe_bar = utils::terminal::USER_ATTENDED.then(|| {
+        let bar = ProgressBar::new_spinner();
+        bar.set_style(utils::terminal::spinner_style());
+        bar.set_message("fetching MEGA nodes...");
+        bar.enable_steady_tick(Duration::from_millis(75));
+        bar
+    });
+
+    let nodes = mega
+        .fetch_own_nodes()
+        .await
+        .context("could net fetch own MEGA nodes")?;
+
+    if let Some(bar) = maybe_bar {
+        bar.finish_and_clear();
+    }
+
+    Ok(nodes)
+}
+
+/// Creates a folder within MEGA, and waits for its creation to be applied to the local nodes.
+pub async fn create_folder(
+    mega: &mega::Client,
+    nodes: &mut mega::Nodes,
+    parent_handle: &str,
+    folder_name: &str,
+) -> Result<()> {
+    let parent_node = nodes
+        .get_node_by_handle(parent_handle)
+        .context("could not find parent folder")?;
+
+    mega.create_folder(parent_node, folder_name)
+        .await
+        .context("could not create folder within MEGA")?;
+
+    let mut is_applied = false;
+    while !is_applied {
+        let events = mega.wait_events(nodes).await?;
+        is_applied = events.events().iter().any(|event| match event {
+            mega::Event::NodeCreated { nodes } => nodes
+                .iter()
+                .any(|node| node.name() == folder_name && node.parent() == Some(parent_handle)),
+            _ => false,
+        });
+        nodes.apply_events(events)?;
+    }
+
+    Ok(())
+}
+
+/// Returns whether the local file is identical to the remote file.
+pub async fn is_file_identical(
+    maybe_bar: Option<&ProgressBar>,
+    node: &mega::Node,
+    local_path: &Path,
+) -> Result<bool> {
+    if !local_path.exists() {
+        return Ok(false);
+    }
+
+    if !local_path.is_file() {
+        // TODO: should we do anything fancier here ?
+        return Ok(false);
+    }
+
+    let Some(remote_mac) = node.condensed_mac() else {
+        return Ok(false);
+    };
+
+    let file = File::open(local_path).await?;
+    let size = file.metadata().await?.len();
+
+    if size != node.size() {
+        return Ok(false);
+    }
+
+    let reader = match maybe_bar.cloned() {
+        Some(bar) => {
+            bar.set_style(utils::terminal::standard_progress_style());
+            bar.set_message(format!("checking `{0}`...", local_path.display()));
+
+            bar.set_position(0);
+            bar.set_length(size);
+            bar.reset();
+
+            Either::Left(file.compat().report_progress(
+                Duration::from_millis(100),
+                move |bytes_read| {
+                    bar.set_position(bytes_read as u64);
+                },
+            ))
+        }
+        None => Either::Right(file.compat()),
+    };
+
+    let local_mac = mega::compute_condensed_mac(
+        reader,
+        size,
+        node.aes_key(),
+        node.aes_iv().unwrap_or(&[0u8; 8]),
+    )
+    .await?;
+
+    Ok(&local_mac == remote_mac)
+}
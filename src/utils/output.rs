@@ -0,0 +1,56 @@
+use std::io::Write;
+
+use clap::ValueEnum;
+use serde::Serialize;
+
+use crate::Result;
+
+/// The format in which results are displayed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, ValueEnum)]
+#[value(rename_all = "kebab-case")]
+pub enum OutputFormat {
+    /// Human-readable text
+    Text,
+    /// A single JSON document
+    Json,
+    /// One JSON object per line (newline-delimited JSON)
+    Ndjson,
+}
+
+impl OutputFormat {
+    /// Returns whether the format is a machine-readable one.
+    pub fn is_json(self) -> bool {
+        matches!(self, OutputFormat::Json | OutputFormat::Ndjson)
+    }
+}
+
+/// Writes multiple items to the standard output, as a JSON array or as newline-delimited JSON.
+pub fn print_json_items<T: Serialize>(format: OutputFormat, items: &[T]) -> Result<()> {
+    let mut stdout = std::io::stdout().lock();
+
+    if format == OutputFormat::Ndjson {
+        for item in items {
+            serde_json::to_writer(&mut stdout, item)?;
+            writeln!(stdout)?;
+        }
+    } else {
+        serde_json::to_writer_pretty(&mut stdout, items)?;
+        writeln!(stdout)?;
+    }
+
+    Ok(())
+}
+
+/// Writes a single item to the standard output, as pretty-printed (or single-line) JSON.
+pub fn print_json_item<T: Serialize>(format: OutputFormat, item: &T) -> Result<()> {
+    let mut stdout = std::io::stdout().lock();
+
+    if format == OutputFormat::Ndjson {
+        serde_json::to_writer(&mut stdout, item)?;
+    } else {
+        serde_json::to_writer_pretty(&mut stdout, item)?;
+    }
+    writeln!(stdout)?;
+
+    Ok(())
+}
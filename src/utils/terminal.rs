@@ -5,9 +5,9 @@ use indicatif::style::ProgressStyle;
 use indicatif::{DecimalBytes, ProgressState};
 use once_cell::sync::Lazy;
 
-pub static USER_ATTENDED: Lazy<bool> = Lazy::new(|| console::user_attended());
+pub static USER_ATTENDED: Lazy<bool> = Lazy::new(console::user_attended);
 pub static COLOR_ENABLED: Lazy<bool> = Lazy::new(|| {
-    let no_color = env::var("NO_COLOR").map_or(false, |value| !value.is_empty());
+    let no_color = env::var("NO_COLOR").is_ok_and(|value| !value.is_empty());
     !no_color && *USER_ATTENDED
 });
 
@@ -59,9 +59,9 @@ pub fn generate_template(width: usize, extent: usize, ch1: char, ch2: char) -> V
 
             format!(
                 "{before}{extent}{after}",
-                before = std::iter::repeat(ch2).take(pad_start).collect::<String>(),
-                extent = std::iter::repeat(ch1).take(within).collect::<String>(),
-                after = std::iter::repeat(ch2).take(pad_end).collect::<String>(),
+                before = std::iter::repeat_n(ch2, pad_start).collect::<String>(),
+                extent = std::iter::repeat_n(ch1, within).collect::<String>(),
+                after = std::iter::repeat_n(ch2, pad_end).collect::<String>(),
             )
         })
         .collect()